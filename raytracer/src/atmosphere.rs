@@ -0,0 +1,217 @@
+use std::f64::INFINITY;
+
+use rand::Rng;
+
+use crate::{
+    basic::{
+        rand_1,
+        ray::Ray,
+        vec3::{RGBColor, Vec3},
+    },
+    pdf::phase_pdf::henyey_greenstein,
+};
+
+#[derive(Clone, Copy)]
+pub enum AtmosphereDensity {
+    Homogeneous,
+    // 密度为 exp(-falloff * (y - base_height)), 模拟贴近地面的雾
+    HeightFalloff { base_height: f64, falloff: f64 },
+}
+
+pub enum AtmosphereEvent {
+    Scatter(f64, RGBColor), // 在 ray.at(t) 处散射, 及该次采样的权重
+    Pass(RGBColor),         // 未散射, 到达下一个碰撞点 (或背景) 的权重
+}
+
+#[derive(Clone)]
+pub struct Atmosphere {
+    // 充满整个场景的介质, 作用于所有光线在两次碰撞之间的路径
+    pub sigma_t: RGBColor, // 密度为 1 处的 extinction
+    pub albedo: RGBColor,
+    pub g: f64, // Henyey-Greenstein 相函数参数
+    pub density: AtmosphereDensity,
+}
+
+impl Atmosphere {
+    pub fn new_homogeneous(sigma_t: RGBColor, albedo: RGBColor, g: f64) -> Self {
+        Self {
+            sigma_t,
+            albedo,
+            g,
+            density: AtmosphereDensity::Homogeneous,
+        }
+    }
+
+    pub fn new_height_falloff(
+        sigma_t: RGBColor,
+        albedo: RGBColor,
+        g: f64,
+        base_height: f64,
+        falloff: f64,
+    ) -> Self {
+        Self {
+            sigma_t,
+            albedo,
+            g,
+            density: AtmosphereDensity::HeightFalloff {
+                base_height,
+                falloff,
+            },
+        }
+    }
+
+    fn density_at(&self, y: f64) -> f64 {
+        match self.density {
+            AtmosphereDensity::Homogeneous => 1.,
+            AtmosphereDensity::HeightFalloff {
+                base_height,
+                falloff,
+            } => f64::exp(-falloff * (y - base_height)),
+        }
+    }
+
+    // 沿单位方向前进 s 距离的密度积分为 a * (1 - exp(-b * s)) / b
+    fn depth_coe(&self, ray: &Ray) -> (f64, f64) {
+        match self.density {
+            AtmosphereDensity::Homogeneous => (1., 0.),
+            AtmosphereDensity::HeightFalloff { falloff, .. } => {
+                (self.density_at(ray.orig.y), falloff * ray.dir.to_unit().y)
+            }
+        }
+    }
+
+    fn unit_depth(a: f64, b: f64, s: f64) -> f64 {
+        if s == INFINITY {
+            if b > 0. {
+                a / b
+            } else {
+                INFINITY
+            }
+        } else if b.abs() < 1e-9 {
+            a * s
+        } else {
+            a * (1. - f64::exp(-b * s)) / b
+        }
+    }
+
+    // unit_depth 的反函数, 无法达到时返回无穷远
+    fn unit_distance(a: f64, b: f64, depth: f64) -> f64 {
+        if b.abs() < 1e-9 {
+            depth / a
+        } else {
+            let k = 1. - depth * b / a;
+            if k <= 0. {
+                INFINITY
+            } else {
+                -f64::ln(k) / b
+            }
+        }
+    }
+
+    fn transmittance_dis(&self, a: f64, b: f64, dis: f64) -> RGBColor {
+        let depth = Self::unit_depth(a, b, dis);
+        let tr = |sigma: f64| {
+            if sigma <= 0. {
+                1.
+            } else {
+                f64::exp(-sigma * depth)
+            }
+        };
+        RGBColor::new(tr(self.sigma_t.x), tr(self.sigma_t.y), tr(self.sigma_t.z))
+    }
+
+    pub fn transmittance(&self, ray: &Ray, t_max: f64) -> RGBColor {
+        let (a, b) = self.depth_coe(ray);
+        self.transmittance_dis(a, b, t_max * ray.dir.length())
+    }
+
+    // 在 ray 的 (0, t_max) 区间内采样散射点, 随机选取一个通道采样自由程 (spectral MIS)
+    pub fn sample(&self, ray: &Ray, t_max: f64) -> AtmosphereEvent {
+        if self.sigma_t.is_zero() {
+            return AtmosphereEvent::Pass(RGBColor::new(1., 1., 1.));
+        }
+        let ray_len = ray.dir.length();
+        let dis_max = t_max * ray_len;
+        let (a, b) = self.depth_coe(ray);
+
+        let channel = rand::thread_rng().gen_range(0..3);
+        let dis = if self.sigma_t[channel] > 0. {
+            Self::unit_distance(a, b, -f64::ln(rand_1()) / self.sigma_t[channel])
+        } else {
+            INFINITY
+        };
+
+        if dis < dis_max {
+            let tr = self.transmittance_dis(a, b, dis);
+            let sigma_t = self.sigma_t * self.density_at(ray.orig.y + ray.dir.y / ray_len * dis);
+            let pdf = (sigma_t * tr).average();
+            AtmosphereEvent::Scatter(dis / ray_len, sigma_t * self.albedo * tr / pdf)
+        } else {
+            let tr = self.transmittance_dis(a, b, dis_max);
+            AtmosphereEvent::Pass(tr / tr.average())
+        }
+    }
+
+    pub fn phase(&self, dir_in: &Vec3, dir_out: &Vec3) -> f64 {
+        henyey_greenstein(Vec3::dot(&dir_in.to_unit(), &dir_out.to_unit()), self.g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::basic::vec3::Point3;
+
+    use super::*;
+
+    #[test]
+    fn homogeneous_transmittance() {
+        let atm =
+            Atmosphere::new_homogeneous(RGBColor::new(0.1, 0.2, 0.), RGBColor::new(1., 1., 1.), 0.);
+        // 方向向量长度为 2, t_max = 3 对应距离 6
+        let ray = Ray::new(Point3::new(1., 5., 2.), Vec3::new(0., 0., 2.), 0.);
+        let tr = atm.transmittance(&ray, 3.);
+        assert!((tr.x - (-0.6f64).exp()).abs() < 1e-9);
+        assert!((tr.y - (-1.2f64).exp()).abs() < 1e-9);
+        assert!((tr.z - 1.).abs() < 1e-9);
+        assert!(atm.transmittance(&ray, INFINITY).x.abs() < 1e-9);
+    }
+
+    #[test]
+    fn height_falloff_transmittance() {
+        let atm = Atmosphere::new_height_falloff(
+            RGBColor::new(0.5, 0.5, 0.5),
+            RGBColor::new(1., 1., 1.),
+            0.,
+            1.,
+            0.8,
+        );
+        // 与沿光线数值积分的光学深度比较
+        let numeric = |ray: &Ray, dis: f64| {
+            let n = 100000;
+            let dir = ray.dir.to_unit();
+            let ds = dis / n as f64;
+            let depth: f64 = (0..n)
+                .map(|i| atm.density_at(ray.orig.y + dir.y * (i as f64 + 0.5) * ds) * ds)
+                .sum();
+            f64::exp(-0.5 * depth)
+        };
+        let dirs = [
+            Vec3::new(1., 0., 0.),
+            Vec3::new(0., 1., 0.),
+            Vec3::new(0., -1., 0.),
+            Vec3::new(1., 0.5, -1.),
+        ];
+        for dir in &dirs {
+            let ray = Ray::new(Point3::new(0., 2., 0.), *dir, 0.);
+            let tr = atm.transmittance(&ray, 4. / dir.length());
+            assert!((tr.x - numeric(&ray, 4.)).abs() < 1e-6);
+        }
+
+        // 竖直向上至无穷远时光学深度有限: exp(-0.8) / 0.8
+        let up = Ray::new(Point3::new(0., 2., 0.), Vec3::new(0., 1., 0.), 0.);
+        let expected = f64::exp(-0.5 * f64::exp(-0.8) / 0.8);
+        assert!((atm.transmittance(&up, INFINITY).x - expected).abs() < 1e-9);
+        let down = Ray::new(Point3::new(0., 2., 0.), Vec3::new(0., -1., 0.), 0.);
+        assert!(atm.transmittance(&down, INFINITY).x.abs() < 1e-9);
+    }
+}
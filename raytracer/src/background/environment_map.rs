@@ -0,0 +1,304 @@
+use std::f64::consts::PI;
+
+use crate::{
+    basic::{
+        clamp_hoi,
+        color::ColorSpace,
+        degree_to_radian, rand_1,
+        vec3::{RGBColor, Vec3},
+    },
+    texture::image_texture::ImageTexture,
+};
+
+use super::Background;
+
+// 等距柱状投影 (equirectangular) 的 u,v 与方向的互相转换, 与 Sphere::get_sphere_uv 一致
+pub fn dir_to_uv(dir: &Vec3) -> (f64, f64) {
+    let d = dir.to_unit();
+    let theta = f64::acos(clamp_hoi(-d.y, -1., 1.));
+    let phi = f64::atan2(-d.z, d.x) + PI;
+
+    (
+        clamp_hoi(phi / (2. * PI), 0., 1.),
+        clamp_hoi(theta / PI, 0., 1.),
+    )
+}
+
+pub fn uv_to_dir(u: f64, v: f64) -> Vec3 {
+    let phi = u * 2. * PI;
+    let theta = v * PI;
+
+    Vec3::new(
+        -f64::cos(phi) * f64::sin(theta),
+        -f64::cos(theta),
+        f64::sin(phi) * f64::sin(theta),
+    )
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct EquirectDistribution {
+    // 按像素权重 (亮度 * sin(theta)) 构建的二维分布, 用于重要性采样
+    // 第 0 行对应图像顶部, 即 v = 1
+    width: usize,
+    height: usize,
+    marginal: Vec<f64>,    // 行的累积分布, 长度 height + 1
+    conditional: Vec<f64>, // 每行内列的累积分布, 长度 height * (width + 1)
+    weights: Vec<f64>,
+    total: f64,
+}
+
+impl EquirectDistribution {
+    pub fn new(width: usize, height: usize, lum: &[f64]) -> Self {
+        let mut weights = Vec::<f64>::with_capacity(width * height);
+        for j in 0..height {
+            let sin_theta = f64::sin((j as f64 + 0.5) / height as f64 * PI);
+            for i in 0..width {
+                weights.push(lum[j * width + i].max(0.) * sin_theta);
+            }
+        }
+
+        let mut conditional = Vec::<f64>::with_capacity(height * (width + 1));
+        let mut marginal = vec![0.];
+        for j in 0..height {
+            let row = &weights[j * width..(j + 1) * width];
+            let row_sum: f64 = row.iter().sum();
+            let mut acc = 0.;
+            conditional.push(0.);
+            for w in row {
+                acc += if row_sum > 0. {
+                    w / row_sum
+                } else {
+                    1. / width as f64
+                };
+                conditional.push(acc);
+            }
+            marginal.push(marginal[j] + row_sum);
+        }
+        let total = marginal[height];
+        for m in &mut marginal {
+            *m = if total > 0. { *m / total } else { 0. };
+        }
+
+        Self {
+            width,
+            height,
+            marginal,
+            conditional,
+            weights,
+            total,
+        }
+    }
+
+    fn sample_cdf(cdf: &[f64], x: f64) -> usize {
+        let n = cdf.len() - 1;
+        let idx = cdf.partition_point(|c| *c <= x);
+        idx.saturating_sub(1).min(n - 1)
+    }
+
+    // 返回采样得到的 (u, v)
+    pub fn sample(&self) -> (f64, f64) {
+        let j = Self::sample_cdf(&self.marginal, rand_1());
+        let row = &self.conditional[j * (self.width + 1)..(j + 1) * (self.width + 1)];
+        let i = Self::sample_cdf(row, rand_1());
+
+        (
+            (i as f64 + rand_1()) / self.width as f64,
+            1. - (j as f64 + rand_1()) / self.height as f64,
+        )
+    }
+
+    // (u, v) 处关于立体角的概率密度
+    pub fn pdf(&self, u: f64, v: f64) -> f64 {
+        if self.total <= 0. {
+            return 0.;
+        }
+        let sin_theta = f64::sin(v * PI);
+        if sin_theta <= 0. {
+            return 0.;
+        }
+        let i = (clamp_hoi(u, 0., 1.) * self.width as f64) as usize;
+        let j = (clamp_hoi(1. - v, 0., 1.) * self.height as f64) as usize;
+        let pdf_uv =
+            self.weights[j * self.width + i] * (self.width * self.height) as f64 / self.total;
+
+        pdf_uv / (2. * PI * PI * sin_theta)
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct EnvironmentMap {
+    // 基于等距柱状投影 HDR 图像的环境光照
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<RGBColor>, // 线性空间颜色, 第 0 行为图像顶部
+    pub intensity: f64,
+    sin_rot: f64,
+    cos_rot: f64,
+    dist: EquirectDistribution,
+}
+
+impl EnvironmentMap {
+    pub fn new(
+        width: usize,
+        height: usize,
+        data: Vec<RGBColor>,
+        rotation: f64, // 绕 y 轴旋转角度
+        intensity: f64,
+    ) -> Self {
+        let lum: Vec<f64> = data.iter().map(|c| c.luminance()).collect();
+        let radians = degree_to_radian(rotation);
+
+        Self {
+            width,
+            height,
+            data,
+            intensity,
+            sin_rot: f64::sin(radians),
+            cos_rot: f64::cos(radians),
+            dist: EquirectDistribution::new(width, height, &lum),
+        }
+    }
+
+    // 经 ImageTexture 读入, .hdr 文件视为线性数据, 其余格式按 color_space 解码
+    pub fn new_from_file(
+        file_name: &str,
+        color_space: ColorSpace,
+        rotation: f64,
+        intensity: f64,
+    ) -> Result<Self, String> {
+        let image = ImageTexture::new_from_file(file_name, color_space)?;
+        Ok(Self::new(
+            image.width(),
+            image.height(),
+            image.pixels().to_vec(),
+            rotation,
+            intensity,
+        ))
+    }
+
+    fn to_local(&self, dir: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.cos_rot * dir.x - self.sin_rot * dir.z,
+            dir.y,
+            self.sin_rot * dir.x + self.cos_rot * dir.z,
+        )
+    }
+
+    fn to_world(&self, dir: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.cos_rot * dir.x + self.sin_rot * dir.z,
+            dir.y,
+            -self.sin_rot * dir.x + self.cos_rot * dir.z,
+        )
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn value(&self, dir: &Vec3) -> RGBColor {
+        let (u, v) = dir_to_uv(&self.to_local(dir));
+        let i = (u * self.width as f64) as usize;
+        let j = ((1. - v) * self.height as f64) as usize;
+        let j = j.min(self.height - 1);
+
+        self.data[j * self.width + i.min(self.width - 1)] * self.intensity
+    }
+
+    fn sampleable(&self) -> bool {
+        true
+    }
+
+    fn pdf_value(&self, dir: &Vec3) -> f64 {
+        let (u, v) = dir_to_uv(&self.to_local(dir));
+        self.dist.pdf(u, v)
+    }
+
+    fn rand_dir(&self) -> Vec3 {
+        let (u, v) = self.dist.sample();
+        self.to_world(&uv_to_dir(u, v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use image::RgbImage;
+
+    use super::*;
+
+    fn test_map() -> EnvironmentMap {
+        let (width, height) = (8, 4);
+        let data = (0..width * height)
+            .map(|k| {
+                let w = ((k * 7) % 5) as f64;
+                RGBColor::new(w, w * 0.5, 0.2)
+            })
+            .collect();
+        EnvironmentMap::new(width, height, data, 30., 1.)
+    }
+
+    #[test]
+    fn pdf_integrates_to_one() {
+        let env = test_map();
+        // 在 (u, v) 网格上积分, d\omega = 2 * PI^2 * sin(theta) du dv
+        let n = 256;
+        let mut sum = 0.;
+        for i in 0..2 * n {
+            for j in 0..n {
+                let u = (i as f64 + 0.5) / (2 * n) as f64;
+                let v = (j as f64 + 0.5) / n as f64;
+                let dir = env.to_world(&uv_to_dir(u, v));
+                let sin_theta = f64::sin(v * PI);
+                sum += env.pdf_value(&dir) * 2. * PI * PI * sin_theta / (2 * n * n) as f64;
+            }
+        }
+        assert!((sum - 1.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn samples_match_pdf() {
+        let env = test_map();
+        let (width, height) = (env.width, env.height);
+        let n = 200000;
+        let mut cnt = vec![0_usize; width * height];
+        for _i in 0..n {
+            let dir = env.rand_dir();
+            assert!(env.pdf_value(&dir) > 0.);
+            let (u, v) = dir_to_uv(&env.to_local(&dir));
+            let i = ((u * width as f64) as usize).min(width - 1);
+            let j = (((1. - v) * height as f64) as usize).min(height - 1);
+            cnt[j * width + i] += 1;
+        }
+
+        // 每个像素被采样的频率应与该像素上 pdf 的积分 (即其权重占比) 一致
+        for (k, c) in cnt.iter().enumerate() {
+            let expected = env.dist.weights[k] / env.dist.total;
+            assert!((*c as f64 / n as f64 - expected).abs() < 0.005);
+        }
+    }
+
+    #[test]
+    fn srgb_file_decoded() {
+        let path = std::env::temp_dir().join("environment_map_test.png");
+        let path = path.to_str().unwrap();
+        RgbImage::from_pixel(4, 2, image::Rgb([128, 128, 128]))
+            .save(path)
+            .unwrap();
+
+        // 8 位 sRGB 贴图需解码到线性空间, 128 -> 约 0.216
+        let env = EnvironmentMap::new_from_file(path, ColorSpace::SRGB, 0., 1.).unwrap();
+        let col = env.value(&Vec3::new(1., 0., 0.));
+        assert!((col.x - 0.2158).abs() < 1e-3);
+        let env = EnvironmentMap::new_from_file(path, ColorSpace::Linear, 0., 1.).unwrap();
+        assert!((env.value(&Vec3::new(1., 0., 0.)).x - 128. / 255.).abs() < 1e-9);
+
+        assert!(
+            EnvironmentMap::new_from_file("not_exist.hdr", ColorSpace::Linear, 0., 1.).is_err()
+        );
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,40 @@
+pub mod environment_map;
+pub mod sky;
+
+use crate::basic::vec3::{RGBColor, Vec3};
+
+pub trait Background: Send + Sync {
+    // 光线未击中任何物体时, 沿 dir 方向得到的颜色
+    fn value(&self, dir: &Vec3) -> RGBColor;
+
+    fn sampleable(&self) -> bool {
+        false
+    }
+
+    fn pdf_value(&self, _dir: &Vec3) -> f64 {
+        0.
+    }
+
+    fn rand_dir(&self) -> Vec3 {
+        Vec3::new(1., 0., 0.)
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct SolidBackground {
+    pub color_value: RGBColor,
+}
+
+impl SolidBackground {
+    pub fn new(color_value: RGBColor) -> Self {
+        Self { color_value }
+    }
+}
+
+impl Background for SolidBackground {
+    fn value(&self, _dir: &Vec3) -> RGBColor {
+        self.color_value
+    }
+}
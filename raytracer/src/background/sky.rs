@@ -0,0 +1,306 @@
+use std::f64::consts::PI;
+
+use crate::basic::{
+    clamp_oi,
+    color::xyz_to_rgb,
+    onb::ONB,
+    rand_1,
+    vec3::{RGBColor, Vec3},
+};
+
+use super::{
+    environment_map::{dir_to_uv, uv_to_dir, EquirectDistribution},
+    Background,
+};
+
+const SUN_ANGULAR_RADIUS: f64 = 0.00465; // 太阳视半径, 约 0.27°
+const SUN_LUMINANCE: f64 = 1.6e6; // 大气层外太阳亮度, 单位 kcd/m^2, 与天空亮度一致
+const TABLE_WIDTH: usize = 128;
+const TABLE_HEIGHT: usize = 64;
+
+// Perez 天空亮度分布, F(theta, gamma)
+fn perez(coe: &[f64; 5], cos_theta: f64, gamma: f64) -> f64 {
+    (1. + coe[0] * f64::exp(coe[1] / cos_theta))
+        * (1. + coe[2] * f64::exp(coe[3] * gamma) + coe[4] * f64::cos(gamma).powi(2))
+}
+
+fn xyy_to_rgb(x: f64, y: f64, lum: f64) -> RGBColor {
+    xyz_to_rgb(x / y * lum, lum, (1. - x - y) / y * lum)
+}
+
+#[derive(Clone)]
+pub struct PreethamSky {
+    // Preetham 解析天空模型, 包含太阳圆盘与地面
+    pub sun_dir: Vec3,
+    pub turbidity: f64, // 浑浊度, 通常取 2 ~ 10
+    pub ground_albedo: RGBColor,
+    pub intensity: f64, // 亮度缩放, 天空亮度的原始单位为 kcd/m^2
+
+    coe_y: [f64; 5],
+    coe_x: [f64; 5],
+    coe_yc: [f64; 5],
+    zenith: [f64; 3], // 天顶处的 Y, x, y
+    norm: [f64; 3],   // F(0, theta_s)
+
+    sun_radiance: RGBColor,
+    ground_radiance: RGBColor,
+    cos_sun_max: f64,
+    sun_disk: bool, // 是否绘制太阳圆盘
+    sun_prob: f64,  // 采样时选择太阳的概率
+    dist: EquirectDistribution,
+}
+
+impl PreethamSky {
+    pub fn new(sun_dir: Vec3, turbidity: f64, ground_albedo: RGBColor, intensity: f64) -> Self {
+        let sun_dir = sun_dir.to_unit();
+        let t = turbidity;
+        let theta_s = f64::acos(clamp_oi(sun_dir.y, 0., 1.));
+
+        let coe_y = [
+            0.1787 * t - 1.4630,
+            -0.3554 * t + 0.4275,
+            -0.0227 * t + 5.3251,
+            0.1206 * t - 2.5771,
+            -0.0670 * t + 0.3703,
+        ];
+        let coe_x = [
+            -0.0193 * t - 0.2592,
+            -0.0665 * t + 0.0008,
+            -0.0004 * t + 0.2125,
+            -0.0641 * t - 0.8989,
+            -0.0033 * t + 0.0452,
+        ];
+        let coe_yc = [
+            -0.0167 * t - 0.2608,
+            -0.0950 * t + 0.0092,
+            -0.0079 * t + 0.2102,
+            -0.0441 * t - 1.6537,
+            -0.0109 * t + 0.0529,
+        ];
+
+        let chi = (4. / 9. - t / 120.) * (PI - 2. * theta_s);
+        let zenith_lum = (4.0453 * t - 4.9710) * f64::tan(chi) - 0.2155 * t + 2.4192;
+        let th = [theta_s.powi(3), theta_s.powi(2), theta_s, 1.];
+        let poly = |k: [f64; 4]| k[0] * th[0] + k[1] * th[1] + k[2] * th[2] + k[3] * th[3];
+        let zenith_x = t * t * poly([0.00166, -0.00375, 0.00209, 0.])
+            + t * poly([-0.02903, 0.06377, -0.03202, 0.00394])
+            + poly([0.11693, -0.21196, 0.06052, 0.25886]);
+        let zenith_y = t * t * poly([0.00275, -0.00610, 0.00317, 0.])
+            + t * poly([-0.04214, 0.08970, -0.04153, 0.00516])
+            + poly([0.15346, -0.26756, 0.06670, 0.26688]);
+
+        let cos_theta_s = f64::cos(theta_s).max(0.001);
+        let norm = [
+            perez(&coe_y, 1., theta_s),
+            perez(&coe_x, 1., theta_s),
+            perez(&coe_yc, 1., theta_s),
+        ];
+
+        // 太阳经大气衰减后的颜色: Rayleigh 散射与气溶胶散射 (波长取 R,G,B 代表值)
+        let sun_radiance = if sun_dir.y > 0. {
+            let m = 1. / (cos_theta_s + 0.15 * (93.885 - theta_s.to_degrees()).powf(-1.253));
+            let beta = 0.04608 * t - 0.04586;
+            let tau = |lambda: f64| {
+                f64::exp(-0.008735 * lambda.powf(-4.08) * m)
+                    * f64::exp(-beta * lambda.powf(-1.3) * m)
+            };
+            RGBColor::new(tau(0.65), tau(0.55), tau(0.45)) * SUN_LUMINANCE * intensity
+        } else {
+            RGBColor::default()
+        };
+
+        let mut sky = Self {
+            sun_dir,
+            turbidity,
+            ground_albedo,
+            intensity,
+            coe_y,
+            coe_x,
+            coe_yc,
+            zenith: [zenith_lum, zenith_x, zenith_y],
+            norm,
+            sun_radiance,
+            ground_radiance: RGBColor::default(),
+            cos_sun_max: f64::cos(SUN_ANGULAR_RADIUS),
+            sun_disk: true,
+            sun_prob: 0.,
+            dist: EquirectDistribution::new(1, 1, &[1.]),
+        };
+
+        // 预计算天空亮度表: 用于重要性采样与地面所受照度
+        let mut table = Vec::<RGBColor>::with_capacity(TABLE_WIDTH * TABLE_HEIGHT);
+        let mut sky_irradiance = RGBColor::default();
+        let mut sky_power = 0.;
+        let d_omega = 2. * PI * PI / (TABLE_WIDTH * TABLE_HEIGHT) as f64;
+        for j in 0..TABLE_HEIGHT {
+            let v = 1. - (j as f64 + 0.5) / TABLE_HEIGHT as f64;
+            let sin_theta = f64::sin(v * PI);
+            for i in 0..TABLE_WIDTH {
+                let u = (i as f64 + 0.5) / TABLE_WIDTH as f64;
+                let dir = uv_to_dir(u, v);
+                let col = sky.sky_radiance(&dir);
+                if dir.y > 0. {
+                    sky_irradiance += col * dir.y * sin_theta * d_omega;
+                }
+                sky_power += col.luminance() * sin_theta * d_omega;
+                table.push(col);
+            }
+        }
+
+        let solid_angle = 2. * PI * (1. - sky.cos_sun_max);
+        let sun_irradiance = sky.sun_radiance * solid_angle * sun_dir.y.max(0.);
+        sky.ground_radiance = ground_albedo * (sky_irradiance + sun_irradiance) / PI;
+
+        let ground_lum = sky.ground_radiance.luminance();
+        let lum: Vec<f64> = (0..TABLE_WIDTH * TABLE_HEIGHT)
+            .map(|k| {
+                let v = 1. - ((k / TABLE_WIDTH) as f64 + 0.5) / TABLE_HEIGHT as f64;
+                if v < 0.5 {
+                    ground_lum
+                } else {
+                    table[k].luminance()
+                }
+            })
+            .collect();
+        sky.dist = EquirectDistribution::new(TABLE_WIDTH, TABLE_HEIGHT, &lum);
+
+        let sun_power = sky.sun_radiance.luminance() * solid_angle;
+        sky.sun_prob = if sun_power > 0. {
+            clamp_oi(sun_power / (sun_power + sky_power), 0.1, 0.9)
+        } else {
+            0.
+        };
+
+        sky
+    }
+
+    // 不绘制太阳圆盘, 用于太阳改由其他光源显式采样的情形
+    pub fn without_sun_disk(mut self) -> Self {
+        self.sun_disk = false;
+        self.sun_prob = 0.;
+        self
+    }
+
+    // 不含太阳圆盘的天空亮度 (仅地平线以上)
+    fn sky_radiance(&self, dir: &Vec3) -> RGBColor {
+        let d = dir.to_unit();
+        if d.y < 0. {
+            return RGBColor::default();
+        }
+        let cos_theta = d.y.max(0.001);
+        let gamma = f64::acos(clamp_oi(Vec3::dot(&d, &self.sun_dir), -1., 1.));
+
+        let lum = self.zenith[0] * perez(&self.coe_y, cos_theta, gamma) / self.norm[0];
+        let x = self.zenith[1] * perez(&self.coe_x, cos_theta, gamma) / self.norm[1];
+        let y = self.zenith[2] * perez(&self.coe_yc, cos_theta, gamma) / self.norm[2];
+
+        let col = xyy_to_rgb(x, y, lum) * self.intensity;
+        RGBColor::new(col.x.max(0.), col.y.max(0.), col.z.max(0.))
+    }
+
+    pub fn sun_radiance(&self) -> RGBColor {
+        self.sun_radiance
+    }
+
+    pub fn sun_solid_angle(&self) -> f64 {
+        2. * PI * (1. - self.cos_sun_max)
+    }
+}
+
+impl Background for PreethamSky {
+    fn value(&self, dir: &Vec3) -> RGBColor {
+        let d = dir.to_unit();
+        if d.y < 0. {
+            return self.ground_radiance;
+        }
+        if self.sun_disk && Vec3::dot(&d, &self.sun_dir) >= self.cos_sun_max {
+            self.sky_radiance(&d) + self.sun_radiance
+        } else {
+            self.sky_radiance(&d)
+        }
+    }
+
+    fn sampleable(&self) -> bool {
+        true
+    }
+
+    fn pdf_value(&self, dir: &Vec3) -> f64 {
+        let d = dir.to_unit();
+        let (u, v) = dir_to_uv(&d);
+        let sun_pdf = if self.sun_disk && Vec3::dot(&d, &self.sun_dir) >= self.cos_sun_max {
+            1. / self.sun_solid_angle()
+        } else {
+            0.
+        };
+
+        self.sun_prob * sun_pdf + (1. - self.sun_prob) * self.dist.pdf(u, v)
+    }
+
+    fn rand_dir(&self) -> Vec3 {
+        if rand_1() < self.sun_prob {
+            // 在太阳圆盘所张圆锥内均匀采样
+            let z = 1. - rand_1() * (1. - self.cos_sun_max);
+            let phi = 2. * PI * rand_1();
+            let r = (1. - z * z).sqrt();
+            ONB::build_from_w(&self.sun_dir).local(&Vec3::new(
+                f64::cos(phi) * r,
+                f64::sin(phi) * r,
+                z,
+            ))
+        } else {
+            let (u, v) = self.dist.sample();
+            uv_to_dir(u, v)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sun_30() -> Vec3 {
+        // 太阳高度角 30°, 位于 +x 方向
+        Vec3::new(f64::cos(PI / 6.), 0.5, 0.)
+    }
+
+    #[test]
+    fn zenith_and_horizon_luminance() {
+        let sky = PreethamSky::new(sun_30(), 3., RGBColor::new(0.3, 0.3, 0.3), 1.);
+        // 由 Preetham 论文公式计算的参考值, 单位 kcd/m^2
+        let lum = |dir: Vec3| sky.value(&dir).luminance();
+        assert!((lum(Vec3::new(0., 1., 0.)) / 5.13916 - 1.).abs() < 1e-2);
+        assert!((lum(Vec3::new(-1., 0., 0.)) / 7.37821 - 1.).abs() < 1e-2);
+        assert!((lum(Vec3::new(1., 0., 0.)) / 17.9152 - 1.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn sun_sampling_consistency() {
+        let sky = PreethamSky::new(sun_30(), 3., RGBColor::new(0.3, 0.3, 0.3), 1.);
+        assert!(sky.sun_prob > 0.);
+        assert!(sky.pdf_value(&sky.sun_dir) > sky.sun_prob / sky.sun_solid_angle());
+
+        // 以 rand_dir 采样估计积分: E[1 / pdf] 为整个球面, E[sun / pdf] 为太阳所张立体角
+        let n = 200000;
+        let (mut sphere, mut sun) = (0., 0.);
+        for _i in 0..n {
+            let dir = sky.rand_dir();
+            let pdf = sky.pdf_value(&dir);
+            assert!(pdf > 0.);
+            sphere += 1. / pdf / n as f64;
+            if Vec3::dot(&dir.to_unit(), &sky.sun_dir) >= sky.cos_sun_max {
+                sun += 1. / pdf / n as f64;
+            }
+        }
+        assert!((sphere / (4. * PI) - 1.).abs() < 0.03);
+        assert!((sun / sky.sun_solid_angle() - 1.).abs() < 0.03);
+
+        // 不绘制太阳圆盘时不再采样太阳
+        let sky = sky.without_sun_disk();
+        let dir = sky.sun_dir;
+        assert_eq!(
+            sky.pdf_value(&dir),
+            sky.dist.pdf(dir_to_uv(&dir).0, dir_to_uv(&dir).1)
+        );
+        assert_eq!(sky.value(&dir), sky.sky_radiance(&dir));
+    }
+}
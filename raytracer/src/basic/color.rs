@@ -0,0 +1,198 @@
+use super::{clamp_oi, vec3::RGBColor};
+
+// CIE 1931 XYZ 转换为线性 sRGB (D65 白点)
+pub fn xyz_to_rgb(x: f64, y: f64, z: f64) -> RGBColor {
+    RGBColor::new(
+        3.2406 * x - 1.5372 * y - 0.4986 * z,
+        -0.9689 * x + 1.8758 * y + 0.0415 * z,
+        0.0557 * x - 0.2040 * y + 1.0570 * z,
+    )
+}
+
+// 分段高斯函数, 用于拟合颜色匹配函数
+fn lobe(lambda: f64, mu: f64, sigma_1: f64, sigma_2: f64) -> f64 {
+    let sigma = if lambda < mu { sigma_1 } else { sigma_2 };
+    f64::exp(-0.5 * ((lambda - mu) / sigma).powi(2))
+}
+
+// CIE 1931 2° 标准观察者颜色匹配函数, 采用 Wyman 等人的解析拟合, 波长单位为 nm
+pub fn cie_xyz(lambda: f64) -> (f64, f64, f64) {
+    let x = 1.056 * lobe(lambda, 599.8, 37.9, 31.0) + 0.362 * lobe(lambda, 442.0, 16.0, 26.7)
+        - 0.065 * lobe(lambda, 501.1, 20.4, 26.2);
+    let y = 0.821 * lobe(lambda, 568.8, 46.9, 40.5) + 0.286 * lobe(lambda, 530.9, 16.3, 31.1);
+    let z = 1.217 * lobe(lambda, 437.0, 11.8, 36.0) + 0.681 * lobe(lambda, 459.0, 26.0, 13.8);
+    (x, y, z)
+}
+
+// 普朗克定律: 黑体在波长 lambda (nm) 处的光谱辐射亮度, 单位为 W / (sr * m^2 * m)
+pub fn planck(lambda: f64, kelvin: f64) -> f64 {
+    const H: f64 = 6.626_070_15e-34;
+    const C: f64 = 2.997_924_58e8;
+    const K: f64 = 1.380_649e-23;
+    let l = lambda * 1e-9;
+    2. * H * C * C / l.powi(5) / (f64::exp(H * C / (l * K * kelvin)) - 1.)
+}
+
+// 色温对应的线性 sRGB 颜色, 亮度 (Y) 归一化为 1
+pub fn blackbody(kelvin: f64) -> RGBColor {
+    if kelvin <= 0. {
+        return RGBColor::default();
+    }
+    let (mut x, mut y, mut z) = (0., 0., 0.);
+    let mut lambda = 380.;
+    while lambda <= 780. {
+        let b = planck(lambda, kelvin);
+        let (cx, cy, cz) = cie_xyz(lambda);
+        x += b * cx;
+        y += b * cy;
+        z += b * cz;
+        lambda += 5.;
+    }
+
+    let col = xyz_to_rgb(x / y, 1., z / y);
+    RGBColor::new(col.x.max(0.), col.y.max(0.), col.z.max(0.))
+}
+
+//=================================================
+
+pub fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(c: f64) -> f64 {
+    let c = clamp_oi(c, 0., 1.);
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1. / 2.4) - 0.055
+    }
+}
+
+pub fn srgb_color_to_linear(col: RGBColor) -> RGBColor {
+    RGBColor::new(
+        srgb_to_linear(col.x),
+        srgb_to_linear(col.y),
+        srgb_to_linear(col.z),
+    )
+}
+
+pub fn linear_color_to_srgb(col: RGBColor) -> RGBColor {
+    RGBColor::new(
+        linear_to_srgb(col.x),
+        linear_to_srgb(col.y),
+        linear_to_srgb(col.z),
+    )
+}
+
+// RGB 转换为 HSV, 色相单位为度, 范围 [0, 360)
+pub fn rgb_to_hsv(col: RGBColor) -> (f64, f64, f64) {
+    let max = col.x.max(col.y).max(col.z);
+    let min = col.x.min(col.y).min(col.z);
+    let delta = max - min;
+
+    let hue = if delta <= 0. {
+        0.
+    } else if max == col.x {
+        60. * ((col.y - col.z) / delta).rem_euclid(6.)
+    } else if max == col.y {
+        60. * ((col.z - col.x) / delta + 2.)
+    } else {
+        60. * ((col.x - col.y) / delta + 4.)
+    };
+    let saturation = if max > 0. { delta / max } else { 0. };
+    (hue, saturation, max)
+}
+
+pub fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> RGBColor {
+    let h = hue.rem_euclid(360.) / 60.;
+    let c = value * saturation;
+    let x = c * (1. - (h.rem_euclid(2.) - 1.).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.),
+        1 => (x, c, 0.),
+        2 => (0., c, x),
+        3 => (0., x, c),
+        4 => (x, 0., c),
+        _ => (c, 0., x),
+    };
+    let m = value - c;
+    RGBColor::new(r + m, g + m, b + m)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ColorSpace {
+    SRGB,   // 8 位颜色贴图, 需解码到线性空间
+    Linear, // 已处于线性空间的颜色, 如 HDR 贴图
+    Raw,    // 非颜色数据, 如法线贴图, 粗糙度贴图, 不做任何转换
+}
+
+impl ColorSpace {
+    // 将以该颜色空间存储的值转换为渲染所用的线性值
+    pub fn decode(&self, col: RGBColor) -> RGBColor {
+        match self {
+            ColorSpace::SRGB => srgb_color_to_linear(col),
+            ColorSpace::Linear | ColorSpace::Raw => col,
+        }
+    }
+}
+
+// "#RRGGBB" 或 "RRGGBB" 形式的 sRGB 颜色, 返回线性空间颜色
+pub fn hex(code: &str) -> Result<RGBColor, String> {
+    let s = code.trim().trim_start_matches('#');
+    if s.len() != 6 || !s.is_ascii() {
+        return Err(format!("Invalid hex color \"{}\"", code));
+    }
+    let channel = |i: usize| {
+        u8::from_str_radix(&s[i..i + 2], 16)
+            .map(|c| srgb_to_linear(c as f64 / 255.))
+            .map_err(|_| format!("Invalid hex color \"{}\"", code))
+    };
+
+    Ok(RGBColor::new(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blackbody_white_point() {
+        // 6500K 接近 D65 白点, 低色温偏红, 高色温偏蓝
+        let d65 = blackbody(6500.);
+        assert!((d65.x / d65.z - 1.).abs() < 0.15);
+        let warm = blackbody(2700.);
+        assert!(warm.x > warm.y && warm.y > warm.z);
+        let cold = blackbody(12000.);
+        assert!(cold.z > cold.x);
+        assert!((warm.luminance() - 1.).abs() < 0.05);
+    }
+
+    #[test]
+    fn hex_and_srgb() {
+        let col = hex("#FF8000").unwrap();
+        assert!((col.x - 1.).abs() < 1e-9);
+        assert!((linear_to_srgb(col.y) - 128. / 255.).abs() < 1e-9);
+        assert!(col.z.abs() < 1e-9);
+        assert!(hex("12345").is_err());
+        assert!(hex("GG0000").is_err());
+    }
+
+    #[test]
+    fn hsv_round_trip() {
+        let (h, s, v) = rgb_to_hsv(RGBColor::new(1., 0.5, 0.));
+        assert!((h - 30.).abs() < 1e-9 && (s - 1.).abs() < 1e-9 && (v - 1.).abs() < 1e-9);
+        for col in [
+            RGBColor::new(0.2, 0.4, 0.6),
+            RGBColor::new(0.9, 0.1, 0.3),
+            RGBColor::new(0.5, 0.5, 0.5),
+            RGBColor::new(0.3, 0.8, 0.1),
+        ] {
+            let (h, s, v) = rgb_to_hsv(col);
+            assert!((hsv_to_rgb(h, s, v) - col).length() < 1e-9);
+        }
+    }
+}
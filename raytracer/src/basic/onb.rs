@@ -32,7 +32,51 @@ impl ONB {
         new_onb
     }
 
+    pub fn build_from_wu(n: &Vec3, t: &Vec3) -> Self {
+        // 以 n 为 w, 将 t 正交化后作为 u, 用于构建切空间
+        let w = n.to_unit();
+        let u = *t - w * Vec3::dot(t, &w);
+        if u.is_zero() {
+            return Self::build_from_w(n);
+        }
+        let u = u.to_unit();
+
+        Self {
+            axis: [u, Vec3::cross(&w, &u), w],
+        }
+    }
+
     pub fn local(&self, a: &Vec3) -> Vec3 {
         self.axis[0] * a[0] + self.axis[1] * a[1] + self.axis[2] * a[2]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_from_wu_orthonormal() {
+        let cases = [
+            (Vec3::new(0., 1., 0.), Vec3::new(1., 0., 0.)),
+            (Vec3::new(1., 2., 3.), Vec3::new(-2., 0.5, 1.)),
+            (Vec3::new(0., 0., -4.), Vec3::new(0.3, 0., 2.)),
+            (Vec3::new(1., 1., 0.), Vec3::new(2., 2., 0.)), // t 与 n 平行, 退化为 build_from_w
+        ];
+        for (n, t) in cases.iter() {
+            let uvw = ONB::build_from_wu(n, t);
+            for i in 0..3 {
+                assert!((uvw.axis[i].length() - 1.).abs() < 1e-9);
+                for j in (i + 1)..3 {
+                    assert!(Vec3::dot(&uvw.axis[i], &uvw.axis[j]).abs() < 1e-9);
+                }
+            }
+            assert!((uvw.w() - n.to_unit()).length() < 1e-9);
+            // u 位于 n, t 张成的平面内且与 t 同向
+            if !Vec3::cross(n, t).is_zero() {
+                assert!(Vec3::dot(&uvw.u(), t) > 0.);
+                assert!(Vec3::dot(&uvw.u(), &Vec3::cross(n, t)).abs() < 1e-9);
+            }
+        }
+    }
+}
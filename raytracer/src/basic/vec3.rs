@@ -154,6 +154,16 @@ impl Vec3 {
 }
 
 impl RGBColor {
+    pub fn luminance(&self) -> f64 {
+        // Rec.709 相对亮度
+        0.2126 * self.x + 0.7152 * self.y + 0.0722 * self.z
+    }
+
+    pub fn average(&self) -> f64 {
+        // 各通道绝对值的平均, 用于逐通道采样时的混合概率
+        (self.x.abs() + self.y.abs() + self.z.abs()) / 3.
+    }
+
     pub fn to_u8_array(self) -> [u8; 3] {
         [self.x as u8, self.y as u8, self.z as u8]
     }
@@ -4,7 +4,7 @@ use rand::Rng;
 
 use super::aabb::AABB;
 use crate::{
-    basic::ray::Ray,
+    basic::{ray::Ray, vec3::RGBColor},
     hittable::{HitRecord, Hittable, HittableList},
 };
 
@@ -109,4 +109,23 @@ impl Hittable for BvhNode {
     fn bounding_box(&self, _time: f64, _dur: f64) -> Option<AABB> {
         Some(self.aabb_box)
     }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> RGBColor {
+        if !self.aabb_box.hit(ray, t_min, t_max) {
+            return RGBColor::new(1., 1., 1.);
+        }
+
+        let mut tr = self.left.as_ref().unwrap().transmittance(ray, t_min, t_max);
+        if !tr.is_zero() {
+            if let Some(right) = &self.right {
+                tr *= right.transmittance(ray, t_min, t_max);
+            }
+        }
+        tr
+    }
+
+    fn power(&self) -> f64 {
+        self.left.as_ref().map_or(0., |obj| obj.power())
+            + self.right.as_ref().map_or(0., |obj| obj.power())
+    }
 }
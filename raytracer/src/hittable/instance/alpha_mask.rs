@@ -0,0 +1,161 @@
+use super::super::{HitRecord, Hittable};
+
+use crate::{
+    basic::{
+        rand_1,
+        ray::Ray,
+        vec3::{Point3, Vec3},
+        INFINITESIMAL,
+    },
+    bvh::aabb::AABB,
+    texture::Texture,
+};
+
+#[derive(Clone, Copy)]
+pub enum AlphaMode {
+    Threshold(f64), // 不透明度低于阈值处被镂空
+    Stochastic,     // 以不透明度为概率命中, 实现半透明
+}
+
+#[derive(Clone)]
+pub struct AlphaMask<TH, TT>
+where
+    TH: Hittable,
+    TT: Texture,
+{
+    // 以纹理亮度为不透明度, 光线穿过被镂空的区域
+    pub obj: TH,
+    pub mask: TT,
+    pub mode: AlphaMode,
+}
+
+impl<TH: Hittable, TT: Texture> AlphaMask<TH, TT> {
+    pub fn new(obj: TH, mask: TT, mode: AlphaMode) -> Self {
+        Self { obj, mask, mode }
+    }
+
+    fn is_opaque(&self, rec: &HitRecord) -> bool {
+        let alpha = self
+            .mask
+            .value(rec.u, rec.v, rec.p, rec.normal, rec.tm)
+            .luminance();
+        match self.mode {
+            AlphaMode::Threshold(threshold) => alpha >= threshold,
+            AlphaMode::Stochastic => rand_1() < alpha,
+        }
+    }
+}
+
+impl<TH: Hittable, TT: Texture> Hittable for AlphaMask<TH, TT> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        let mut t_from = t_min;
+        loop {
+            let rec = self.obj.hit(ray, t_from, t_max)?;
+            if self.is_opaque(&rec) {
+                return Some(rec);
+            }
+
+            // 从镂空处继续向前寻找下一个交点
+            let t_next = rec.t + INFINITESIMAL;
+            if t_next <= t_from {
+                return None;
+            }
+            t_from = t_next;
+        }
+    }
+
+    fn bounding_box(&self, tm: f64, dur: f64) -> Option<AABB> {
+        self.obj.bounding_box(tm, dur)
+    }
+
+    fn power(&self) -> f64 {
+        self.obj.power()
+    }
+
+    fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
+        // rand_dir 同样会采样到镂空处, 概率密度需与之一致; 镂空方向经阴影测试自然不贡献光照
+        self.obj.pdf_value(orig, dir)
+    }
+
+    fn rand_dir(&self, orig: &Vec3) -> Vec3 {
+        self.obj.rand_dir(orig)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::INFINITY;
+
+    use crate::{
+        hittable::{object::rectangle::Rectangle, HittableList},
+        material::lambertian::Lambertian,
+        texture::{
+            solid_color::SolidColor,
+            uv_pattern::{UVPattern, UVPatternKind},
+        },
+    };
+
+    use super::*;
+
+    fn rect(k: f64) -> Rectangle<Lambertian<SolidColor>> {
+        // xz 平面, x, z in [0, 2], y = k
+        let mat = Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5));
+        Rectangle::new(2, 0., 2., 0., 2., k, mat)
+    }
+
+    #[test]
+    fn threshold_cutout() {
+        // 2x2 棋盘格, u,v 均小于 0.5 或均大于 0.5 的格子被镂空
+        let mask = UVPattern::new(
+            UVPatternKind::Checker,
+            SolidColor::new_from_value(1., 1., 1.),
+            SolidColor::new_from_value(0., 0., 0.),
+            2.,
+            2.,
+        );
+        let mut list = HittableList::default();
+        list.add(AlphaMask::new(rect(1.), mask, AlphaMode::Threshold(0.5)));
+        list.add(rect(0.));
+
+        let down = Vec3::new(0., -1., 0.);
+        let opaque = list.hit(&Ray::new(Point3::new(1.5, 5., 0.5), down, 0.), 0., INFINITY);
+        assert!((opaque.unwrap().t - 4.).abs() < 1e-9);
+        // 镂空处穿过上层, 击中下层
+        let hole = list.hit(&Ray::new(Point3::new(0.5, 5., 0.5), down, 0.), 0., INFINITY);
+        assert!((hole.unwrap().t - 5.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stochastic_hit_rate() {
+        let masked = AlphaMask::new(
+            rect(1.),
+            SolidColor::new_from_value(0.3, 0.3, 0.3),
+            AlphaMode::Stochastic,
+        );
+        let ray = Ray::new(Point3::new(1., 5., 1.), Vec3::new(0., -1., 0.), 0.);
+        let n = 100000;
+        let cnt = (0..n)
+            .filter(|_| masked.hit(&ray, 0., INFINITY).is_some())
+            .count();
+        assert!((cnt as f64 / n as f64 - 0.3).abs() < 0.01);
+    }
+
+    #[test]
+    fn pdf_matches_rand_dir() {
+        let masked = AlphaMask::new(
+            rect(1.),
+            SolidColor::new_from_value(0.3, 0.3, 0.3),
+            AlphaMode::Stochastic,
+        );
+        let inner = rect(1.);
+        let orig = Point3::new(0.5, 4., 0.7);
+        for _i in 0..1000 {
+            // 镂空方向的概率密度与原物体一致, 且不随机变化
+            let dir = masked.rand_dir(&orig);
+            let pdf = masked.pdf_value(&orig, &dir);
+            assert!(pdf > 0.);
+            assert_eq!(pdf, inner.pdf_value(&orig, &dir));
+            assert_eq!(pdf, masked.pdf_value(&orig, &dir));
+        }
+    }
+}
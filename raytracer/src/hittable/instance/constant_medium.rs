@@ -1,4 +1,4 @@
-use std::f64::{INFINITY, NEG_INFINITY};
+use std::f64::{consts::LN_2, INFINITY, NEG_INFINITY};
 
 use super::super::{HitRecord, Hittable};
 
@@ -10,81 +10,123 @@ use crate::{
         INFINITESIMAL,
     },
     bvh::aabb::AABB,
-    material::isotropic::Isotropic,
+    material::{isotropic::Isotropic, Material},
     texture::{solid_color::SolidColor, Texture},
 };
 
 #[derive(Clone)]
-pub struct ConstantMedium<TH, TT>
+pub struct ConstantMedium<TH, TM, TE = SolidColor>
 where
     TH: Hittable,
-    TT: Texture,
+    TM: Material,
+    TE: Texture,
 {
     pub boundary: TH,
-    pub phase_function: Isotropic<TT>,
+    pub phase_function: TM, // 相函数, 如 Isotropic, HenyeyGreenstein
+    pub emission: TE,       // 单位密度单位长度的自发光, 默认为黑色
     neg_inv_density: f64,
 }
 
-impl<TH: Hittable, TT: Texture> ConstantMedium<TH, TT> {
-    pub fn new(boundary: TH, d: f64, albedo: TT) -> Self {
+impl<TH: Hittable, TM: Material> ConstantMedium<TH, TM> {
+    pub fn new_with_phase(boundary: TH, d: f64, phase_function: TM) -> Self {
         Self {
             boundary,
+            phase_function,
+            emission: SolidColor::new_from_value(0., 0., 0.),
             neg_inv_density: -1. / d,
-            phase_function: Isotropic::new(albedo),
         }
     }
 }
 
-impl<TH: Hittable> ConstantMedium<TH, SolidColor> {
+impl<TH: Hittable, TT: Texture> ConstantMedium<TH, Isotropic<TT>> {
+    pub fn new(boundary: TH, d: f64, albedo: TT) -> Self {
+        Self::new_with_phase(boundary, d, Isotropic::new(albedo))
+    }
+}
+
+impl<TH: Hittable> ConstantMedium<TH, Isotropic<SolidColor>> {
     pub fn new_from_color(boundary: TH, d: f64, color_value: RGBColor) -> Self {
-        Self {
-            boundary,
-            neg_inv_density: -1. / d,
-            phase_function: Isotropic::new_from_color(color_value),
+        Self::new_with_phase(boundary, d, Isotropic::new_from_color(color_value))
+    }
+}
+
+impl<TH: Hittable, TM: Material, TE: Texture> ConstantMedium<TH, TM, TE> {
+    pub fn with_emission<TE2: Texture>(self, emission: TE2) -> ConstantMedium<TH, TM, TE2> {
+        ConstantMedium {
+            boundary: self.boundary,
+            phase_function: self.phase_function,
+            emission,
+            neg_inv_density: self.neg_inv_density,
         }
     }
 }
 
-impl<TH: Hittable, TT: Texture> Hittable for ConstantMedium<TH, TT> {
+// 光线在 t_min ~ t_max 内位于边界内部的区间 (t_enter, t_exit)
+pub fn medium_segment<TH: Hittable>(
+    boundary: &TH,
+    ray: &Ray,
+    t_min: f64,
+    t_max: f64,
+) -> Option<(f64, f64)> {
+    let hit_rec_1 = boundary.hit(ray, NEG_INFINITY, INFINITY)?;
+    let hit_rec_2 = boundary.hit(ray, hit_rec_1.t + INFINITESIMAL, INFINITY)?;
+
+    let t_enter = hit_rec_1.t.max(t_min).max(0.);
+    let t_exit = hit_rec_2.t.min(t_max);
+    if t_enter > t_exit || f64_equal(t_enter, t_exit) {
+        None
+    } else {
+        Some((t_enter, t_exit))
+    }
+}
+
+// 介质内碰撞点的切向量, 垂直于光线方向; 光线接近竖直时改用 x 轴, 避免叉积为 0
+pub fn medium_tangent(dir: &Vec3) -> Vec3 {
+    let axis = if dir.y.abs() > 0.9 * dir.length() {
+        Vec3::new(1., 0., 0.)
+    } else {
+        Vec3::new(0., 1., 0.)
+    };
+    Vec3::cross(dir, &axis).to_unit()
+}
+
+impl<TH: Hittable, TM: Material, TE: Texture> Hittable for ConstantMedium<TH, TM, TE> {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
-        if let Some(mut hit_rec_1) = self.boundary.hit(ray, NEG_INFINITY, INFINITY) {
-            if let Some(mut hit_rec_2) =
-                self.boundary
-                    .hit(ray, hit_rec_1.t + INFINITESIMAL, INFINITY)
-            {
-                if hit_rec_1.t < t_min {
-                    hit_rec_1.t = t_min;
-                }
-                if hit_rec_2.t > t_max {
-                    hit_rec_2.t = t_max;
-                }
-                if hit_rec_1.t < 0. {
-                    hit_rec_1.t = 0.;
-                }
-                if hit_rec_1.t > hit_rec_2.t || f64_equal(hit_rec_1.t, hit_rec_2.t) {
-                    return None;
-                }
-
-                let ray_len = ray.dir.length();
-                let dis_inside_bound = (hit_rec_2.t - hit_rec_1.t) * ray_len;
-                let hit_dis = self.neg_inv_density * f64::log2(rand_1());
-
-                if hit_dis > dis_inside_bound {
-                    return None;
-                } else {
-                    let t = hit_rec_1.t + hit_dis / ray_len;
-                    return Some(HitRecord::new(
-                        0.,
-                        0.,
-                        t,
-                        ray,
-                        &Vec3::new(1., 0., 0.),
-                        &self.phase_function,
-                    ));
-                }
+        let (t_enter, t_exit) = medium_segment(&self.boundary, ray, t_min, t_max)?;
+
+        let ray_len = ray.dir.length();
+        let dis_inside_bound = (t_exit - t_enter) * ray_len;
+        let hit_dis = self.neg_inv_density * f64::log2(rand_1());
+
+        if hit_dis > dis_inside_bound {
+            None
+        } else {
+            let t = t_enter + hit_dis / ray_len;
+            let mut rec = HitRecord::new(
+                0.,
+                0.,
+                t,
+                ray,
+                &Vec3::new(1., 0., 0.),
+                &Vec3::new(0., 1., 0.),
+                &self.phase_function,
+            );
+            // collision estimator: 自发光除以 extinction (自由程以 2 为底采样, extinction 为 d * ln2)
+            rec.emission = self.emission.value(0., 0., rec.p, rec.normal, rec.tm) / LN_2;
+            Some(rec)
+        }
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> RGBColor {
+        // 与 hit 中自由程的采样方式 (以 2 为底) 一致
+        match medium_segment(&self.boundary, ray, t_min, t_max) {
+            Some((t_enter, t_exit)) => {
+                let dis_inside_bound = (t_exit - t_enter) * ray.dir.length();
+                let tr = f64::powf(2., dis_inside_bound / self.neg_inv_density);
+                RGBColor::new(tr, tr, tr)
             }
+            None => RGBColor::new(1., 1., 1.),
         }
-        None
     }
 
     fn bounding_box(&self, tm: f64, dur: f64) -> Option<AABB> {
@@ -0,0 +1,338 @@
+use super::{
+    super::{HitRecord, Hittable},
+    constant_medium::{medium_segment, medium_tangent},
+};
+
+use crate::{
+    basic::{
+        rand_1,
+        ray::Ray,
+        vec3::{Point3, RGBColor, Vec3},
+        INFINITESIMAL,
+    },
+    bvh::aabb::AABB,
+    material::{isotropic::Isotropic, Material, ScatterRecord},
+    texture::{solid_color::SolidColor, Texture},
+};
+
+#[derive(Clone)]
+pub struct PassThrough;
+// 光线离开介质时的虚拟界面, 光线方向不变, 仅用于携带逐通道采样的权重
+
+impl Material for PassThrough {
+    fn scatter(&self, ray: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
+        if hit_rec.weight.is_zero() {
+            // 光线已被吸收, 无需继续追踪
+            return None;
+        }
+        Some(ScatterRecord::new_specular(
+            Ray::new(hit_rec.p, ray.dir, ray.tm),
+            RGBColor::new(1., 1., 1.),
+        ))
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct HeterogeneousMedium<TH, TT, TM = Isotropic<SolidColor>, TE = SolidColor>
+where
+    TH: Hittable,
+    TT: Texture,
+    TM: Material,
+    TE: Texture,
+{
+    // 非均匀介质: 密度取自三维纹理 (噪声, 体素网格) 在碰撞点处的亮度
+    // 以 spectral tracking 采样碰撞点, 以 ratio tracking 计算透射率
+    pub boundary: TH,
+    pub density: TT,
+    pub phase_function: TM, // 相函数, albedo 应为白色, 散射率由 albedo 字段给出
+    pub sigma_t: RGBColor,  // 单位密度下各通道的 extinction
+    pub albedo: RGBColor,   // scattering / extinction
+    pub emission: TE,       // 单位密度单位长度的自发光, 默认为黑色
+    majorant: f64,          // 消光系数上界
+    exit: PassThrough,
+}
+
+impl<TH: Hittable, TT: Texture> HeterogeneousMedium<TH, TT> {
+    pub fn new(
+        boundary: TH,
+        density: TT,
+        max_density: f64, // 密度纹理的上界
+        sigma_t: RGBColor,
+        albedo: RGBColor,
+    ) -> Self {
+        let phase_function = Isotropic::new_from_color(RGBColor::new(1., 1., 1.));
+        Self::new_with_phase(
+            boundary,
+            density,
+            max_density,
+            sigma_t,
+            albedo,
+            phase_function,
+        )
+    }
+}
+
+impl<TH: Hittable, TT: Texture, TM: Material> HeterogeneousMedium<TH, TT, TM> {
+    pub fn new_with_phase(
+        boundary: TH,
+        density: TT,
+        max_density: f64,
+        sigma_t: RGBColor,
+        albedo: RGBColor,
+        phase_function: TM,
+    ) -> Self {
+        Self {
+            boundary,
+            density,
+            phase_function,
+            sigma_t,
+            albedo,
+            emission: SolidColor::new_from_value(0., 0., 0.),
+            majorant: max_density * sigma_t.x.max(sigma_t.y).max(sigma_t.z),
+            exit: PassThrough,
+        }
+    }
+}
+
+impl<TH: Hittable, TT: Texture, TM: Material, TE: Texture> HeterogeneousMedium<TH, TT, TM, TE> {
+    pub fn with_emission<TE2: Texture>(
+        self,
+        emission: TE2,
+    ) -> HeterogeneousMedium<TH, TT, TM, TE2> {
+        HeterogeneousMedium {
+            boundary: self.boundary,
+            density: self.density,
+            phase_function: self.phase_function,
+            sigma_t: self.sigma_t,
+            albedo: self.albedo,
+            emission,
+            majorant: self.majorant,
+            exit: self.exit,
+        }
+    }
+
+    fn density_at(&self, p: Point3, tm: f64) -> f64 {
+        self.density
+            .value(0., 0., p, Vec3::default(), tm)
+            .luminance()
+            .max(0.)
+    }
+}
+
+impl<TH: Hittable, TT: Texture, TM: Material, TE: Texture> Hittable
+    for HeterogeneousMedium<TH, TT, TM, TE>
+{
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        let (t_enter, t_exit) = medium_segment(&self.boundary, ray, t_min, t_max)?;
+        if self.majorant <= 0. {
+            return None;
+        }
+
+        // spectral tracking: 按各事件加权后的平均概率选择 吸收 / 散射 / 空碰撞
+        let ray_len = ray.dir.length();
+        let mut weight = RGBColor::new(1., 1., 1.);
+        let mut emission = RGBColor::default(); // 在每次 (含空) 碰撞处累积自发光
+        let mut t = t_enter;
+        loop {
+            t += -f64::ln(rand_1()) / self.majorant / ray_len;
+            if t >= t_exit {
+                break;
+            }
+
+            let p = ray.at(t);
+            let density = self.density_at(p, ray.tm);
+            emission += weight * self.emission.value(0., 0., p, Vec3::default(), ray.tm) * density
+                / self.majorant;
+
+            let sigma_t = self.sigma_t * density;
+            let sigma_s = sigma_t * self.albedo;
+            let sigma_a = sigma_t - sigma_s;
+            let sigma_n = RGBColor::new(self.majorant, self.majorant, self.majorant) - sigma_t;
+
+            let p_a = (weight * sigma_a).average();
+            let p_s = (weight * sigma_s).average();
+            let p_n = (weight * sigma_n).average();
+            let sum = p_a + p_s + p_n;
+            if sum <= 0. {
+                return None;
+            }
+
+            let k = rand_1() * sum;
+            if k < p_a {
+                // 被吸收
+                let mut rec = HitRecord::new(
+                    0.,
+                    0.,
+                    t,
+                    ray,
+                    &(-ray.dir).to_unit(),
+                    &medium_tangent(&ray.dir),
+                    &self.exit,
+                );
+                rec.weight = RGBColor::default();
+                rec.emission = emission;
+                return Some(rec);
+            } else if k < p_a + p_s {
+                let mut rec = HitRecord::new(
+                    0.,
+                    0.,
+                    t,
+                    ray,
+                    &(-ray.dir).to_unit(),
+                    &medium_tangent(&ray.dir),
+                    &self.phase_function,
+                );
+                rec.weight = weight * sigma_s * (sum / p_s / self.majorant);
+                rec.emission = emission;
+                return Some(rec);
+            } else {
+                weight *= sigma_n * (sum / p_n / self.majorant);
+            }
+        }
+
+        // 穿过介质: 各通道权重一致且无自发光时与 delta tracking 相同, 直接忽略;
+        // 否则在出射处插入虚拟界面以携带权重与自发光
+        if (weight - RGBColor::new(1., 1., 1.)).length_squared() < INFINITESIMAL
+            && emission.is_zero()
+        {
+            None
+        } else {
+            let mut rec = HitRecord::new(
+                0.,
+                0.,
+                t_exit - INFINITESIMAL / ray_len,
+                ray,
+                &(-ray.dir).to_unit(),
+                &medium_tangent(&ray.dir),
+                &self.exit,
+            );
+            rec.weight = weight;
+            rec.emission = emission;
+            Some(rec)
+        }
+    }
+
+    fn bounding_box(&self, tm: f64, dur: f64) -> Option<AABB> {
+        self.boundary.bounding_box(tm, dur)
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> RGBColor {
+        // ratio tracking
+        let mut tr = RGBColor::new(1., 1., 1.);
+        let (t_enter, t_exit) = match medium_segment(&self.boundary, ray, t_min, t_max) {
+            Some(ret) => ret,
+            None => return tr,
+        };
+        if self.majorant <= 0. {
+            return tr;
+        }
+
+        let ray_len = ray.dir.length();
+        let mut t = t_enter;
+        loop {
+            t += -f64::ln(rand_1()) / self.majorant / ray_len;
+            if t >= t_exit {
+                return tr;
+            }
+            let sigma_t = self.sigma_t * self.density_at(ray.at(t), ray.tm);
+            tr *= RGBColor::new(1., 1., 1.) - sigma_t / self.majorant;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::INFINITY;
+
+    use crate::{
+        hittable::{
+            instance::{rotate::Rotate, translate::Translate, zoom::Zoom},
+            object::sphere::Sphere,
+        },
+        material::lambertian::Lambertian,
+    };
+
+    use super::*;
+
+    fn test_medium(
+        sigma_t: f64,
+    ) -> HeterogeneousMedium<Sphere<Lambertian<SolidColor>>, SolidColor> {
+        let boundary = Sphere::new(
+            Point3::default(),
+            1.,
+            Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5)),
+        );
+        HeterogeneousMedium::new(
+            boundary,
+            SolidColor::new_from_value(1., 1., 1.),
+            1.,
+            RGBColor::new(sigma_t, sigma_t, sigma_t),
+            RGBColor::new(1., 1., 1.),
+        )
+    }
+
+    #[test]
+    fn transmittance_through_instances() {
+        // 沿直径穿过单位球, 透射率为 exp(-2 * sigma_t)
+        let expected = (-2. * 0.5f64).exp();
+        let mov = Vec3::new(10., 0., 0.);
+        let translated = Translate::new(test_medium(0.5), mov);
+        let rotated = Rotate::new(Translate::new(test_medium(0.5), mov), 2, 30.);
+        let zoomed = Zoom::new(test_medium(0.5), Vec3::new(1., 1., 1.));
+
+        // 旋转后的介质: 球心由 (10, 0, 0) 绕 z 轴转至 cen
+        let cen = Vec3::new(
+            10. * 30f64.to_radians().cos(),
+            10. * 30f64.to_radians().sin(),
+            0.,
+        );
+        let cases: [(&dyn Hittable, Point3); 3] = [
+            (&translated, mov),
+            (&rotated, cen),
+            (&zoomed, Point3::default()),
+        ];
+
+        let n = 20000;
+        for (obj, cen) in cases {
+            let ray = Ray::new(cen - Vec3::new(0., 0., 5.), Vec3::new(0., 0., 1.), 0.);
+            let mut sum = RGBColor::default();
+            for _i in 0..n {
+                sum += obj.transmittance(&ray, INFINITESIMAL, INFINITY);
+            }
+            assert!((sum.x / n as f64 - expected).abs() < 0.01);
+        }
+
+        // 未穿过介质时完全透射
+        let miss = Ray::new(Point3::new(0., 0., -5.), Vec3::new(0., 0., 1.), 0.);
+        assert!((translated.transmittance(&miss, INFINITESIMAL, INFINITY)
+            - RGBColor::new(1., 1., 1.))
+        .is_zero());
+    }
+
+    #[test]
+    fn transmittance_under_scale() {
+        // 沿 z 轴拉伸为半轴长 3 的椭球, 介质密度定义在物体空间
+        let medium = Zoom::new(test_medium(0.5), Vec3::new(1., 1., 3.));
+        let ray = Ray::new(Point3::new(0., 0., -10.), Vec3::new(0., 0., 1.), 0.);
+        let n = 20000;
+        // 光线止于椭球中心时穿过一半介质
+        for &(t_max, expected) in &[(10., (-0.5f64).exp()), (20., (-1f64).exp())] {
+            let mut sum = RGBColor::default();
+            for _i in 0..n {
+                sum += medium.transmittance(&ray, INFINITESIMAL, t_max);
+            }
+            assert!((sum.x / n as f64 - expected).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn vertical_ray_tangent() {
+        let medium = Zoom::new(test_medium(100.), Vec3::new(2., 2., 2.));
+        let ray = Ray::new(Point3::new(0., 10., 0.), Vec3::new(0., -1., 0.), 0.);
+        let rec = medium.hit(&ray, INFINITESIMAL, INFINITY).unwrap();
+        assert!(!rec.tangent.is_nan() && (rec.tangent.length() - 1.).abs() < 1e-9);
+        assert!(Vec3::dot(&rec.tangent, &ray.dir).abs() < 1e-9);
+    }
+}
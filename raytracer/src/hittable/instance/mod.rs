@@ -1,6 +1,9 @@
+pub mod alpha_mask;
 pub mod constant_medium;
+pub mod heterogeneous_medium;
 pub mod motion_rotate;
 pub mod motion_translate;
 pub mod rotate;
+pub mod subsurface;
 pub mod translate;
 pub mod zoom;
@@ -6,7 +6,7 @@ use crate::{
     basic::{
         degree_to_radian,
         ray::Ray,
-        vec3::{Point3, Vec3},
+        vec3::{Point3, RGBColor, Vec3},
     },
     bvh::aabb::AABB,
 };
@@ -98,6 +98,16 @@ impl<TH: Hittable> MotionRotate<TH> {
 
         r_dir
     }
+
+    // 将物体空间中的点或向量转回世界空间, 为 rotated_dir 的逆变换
+    fn restored(&self, v: &Vec3, sin_theta: f64, cos_theta: f64) -> Vec3 {
+        let mut ret = *v;
+
+        ret[self.dio[0]] = cos_theta * v[self.dio[0]] + sin_theta * v[self.dio[1]];
+        ret[self.dio[1]] = -sin_theta * v[self.dio[0]] + cos_theta * v[self.dio[1]];
+
+        ret
+    }
 }
 
 impl<TH: Hittable> Hittable for MotionRotate<TH> {
@@ -109,16 +119,10 @@ impl<TH: Hittable> Hittable for MotionRotate<TH> {
         let rotated_ray = Ray::new(orig, dir, ray.tm);
 
         if let Some(mut rec) = self.obj.hit(&rotated_ray, t_min, t_max) {
-            let mut p = rec.p;
-            let mut normal = rec.normal;
+            let normal = self.restored(&rec.normal, sin_theta, cos_theta);
 
-            p[self.dio[0]] = cos_theta * rec.p[self.dio[0]] + sin_theta * rec.p[self.dio[1]];
-            p[self.dio[1]] = -sin_theta * rec.p[self.dio[0]] + cos_theta * rec.p[self.dio[1]];
-
-            normal[0] = cos_theta * rec.normal[0] + sin_theta * rec.normal[2];
-            normal[2] = -sin_theta * rec.normal[0] + cos_theta * rec.normal[2];
-
-            rec.p = p;
+            rec.p = self.restored(&rec.p, sin_theta, cos_theta);
+            rec.tangent = self.restored(&rec.tangent, sin_theta, cos_theta);
             rec.set_face_normal(&rotated_ray, &normal);
 
             Some(rec)
@@ -165,4 +169,17 @@ impl<TH: Hittable> Hittable for MotionRotate<TH> {
 
         Some(output_box)
     }
+
+    fn power(&self) -> f64 {
+        self.obj.power()
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> RGBColor {
+        let (sin_theta, cos_theta) = self.get_trigonometric(ray.tm);
+        let orig = self.rotated_orig(&ray.orig, sin_theta, cos_theta);
+        let dir = self.rotated_dir(&ray.dir, sin_theta, cos_theta);
+
+        self.obj
+            .transmittance(&Ray::new(orig, dir, ray.tm), t_min, t_max)
+    }
 }
@@ -1,7 +1,10 @@
 use super::super::{HitRecord, Hittable};
 
 use crate::{
-    basic::{ray::Ray, vec3::Vec3},
+    basic::{
+        ray::Ray,
+        vec3::{RGBColor, Vec3},
+    },
     bvh::aabb::AABB,
 };
 
@@ -48,4 +51,18 @@ impl<TH: Hittable> Hittable for MotionTranslate<TH> {
             &AABB::new(obj_box.min + self.mov, obj_box.max + self.mov),
         ))
     }
+
+    fn power(&self) -> f64 {
+        self.obj.power()
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> RGBColor {
+        let k = (ray.tm - self.tm) / self.dur;
+        if k.is_sign_negative() || k > 1. {
+            return RGBColor::new(1., 1., 1.);
+        }
+
+        let moved_ray = Ray::new(ray.orig - self.mov * k, ray.dir, ray.tm);
+        self.obj.transmittance(&moved_ray, t_min, t_max)
+    }
 }
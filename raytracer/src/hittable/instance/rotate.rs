@@ -6,7 +6,7 @@ use crate::{
     basic::{
         degree_to_radian,
         ray::Ray,
-        vec3::{Point3, Vec3},
+        vec3::{Point3, RGBColor, Vec3},
     },
     bvh::aabb::AABB,
 };
@@ -94,6 +94,16 @@ impl<TH: Hittable> Rotate<TH> {
 
         r_dir
     }
+
+    // 将物体空间中的点或向量转回世界空间, 为 rotated_dir 的逆变换
+    fn restored(&self, v: &Vec3) -> Vec3 {
+        let mut ret = *v;
+
+        ret[self.dio[0]] = self.cos_theta * v[self.dio[0]] + self.sin_theta * v[self.dio[1]];
+        ret[self.dio[1]] = -self.sin_theta * v[self.dio[0]] + self.cos_theta * v[self.dio[1]];
+
+        ret
+    }
 }
 
 impl<TH: Hittable> Hittable for Rotate<TH> {
@@ -104,18 +114,10 @@ impl<TH: Hittable> Hittable for Rotate<TH> {
         let rotated_ray = Ray::new(orig, dir, ray.tm);
 
         if let Some(mut rec) = self.obj.hit(&rotated_ray, t_min, t_max) {
-            let mut p = rec.p;
-            let mut normal = rec.normal;
+            let normal = self.restored(&rec.normal);
 
-            p[self.dio[0]] =
-                self.cos_theta * rec.p[self.dio[0]] + self.sin_theta * rec.p[self.dio[1]];
-            p[self.dio[1]] =
-                -self.sin_theta * rec.p[self.dio[0]] + self.cos_theta * rec.p[self.dio[1]];
-
-            normal[0] = self.cos_theta * rec.normal[0] + self.sin_theta * rec.normal[2];
-            normal[2] = -self.sin_theta * rec.normal[0] + self.cos_theta * rec.normal[2];
-
-            rec.p = p;
+            rec.p = self.restored(&rec.p);
+            rec.tangent = self.restored(&rec.tangent);
             rec.set_face_normal(&rotated_ray, &normal);
 
             Some(rec)
@@ -128,6 +130,18 @@ impl<TH: Hittable> Hittable for Rotate<TH> {
         Some(self.aabb_box)
     }
 
+    fn power(&self) -> f64 {
+        self.obj.power()
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> RGBColor {
+        let orig = self.rotated_orig(&ray.orig);
+        let dir = self.rotated_dir(&ray.dir);
+
+        self.obj
+            .transmittance(&Ray::new(orig, dir, ray.tm), t_min, t_max)
+    }
+
     fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
         let obj_orig = self.rotated_orig(orig);
         let obj_dir = self.rotated_dir(dir);
@@ -138,6 +152,6 @@ impl<TH: Hittable> Hittable for Rotate<TH> {
     fn rand_dir(&self, orig: &Vec3) -> Vec3 {
         let obj_orig = self.rotated_orig(orig);
 
-        self.obj.rand_dir(&obj_orig)
+        self.restored(&self.obj.rand_dir(&obj_orig))
     }
 }
@@ -0,0 +1,167 @@
+use rand::Rng;
+
+use super::{
+    super::{HitRecord, Hittable},
+    constant_medium::medium_tangent,
+};
+
+use crate::{
+    basic::{
+        rand_1,
+        ray::Ray,
+        vec3::{Point3, RGBColor, Vec3},
+    },
+    bvh::aabb::AABB,
+    material::{dielectric::Dielectric, isotropic::Isotropic},
+    texture::solid_color::SolidColor,
+};
+
+#[derive(Clone)]
+pub struct Subsurface<TH>
+where
+    TH: Hittable,
+{
+    // 次表面散射: 边界为 Dielectric 折射面, 内部为逐通道随机游走的介质
+    // 适用于皮肤, 蜡烛, 大理石等半透明物体
+    pub boundary: TH,
+    pub surface: Dielectric,
+    pub phase_function: Isotropic<SolidColor>,
+    pub sigma_t: RGBColor, // extinction, 各通道平均自由程的倒数
+    pub sigma_s: RGBColor, // scattering, sigma_t * albedo
+}
+
+impl<TH: Hittable> Subsurface<TH> {
+    pub fn new(boundary: TH, ir: f64, mean_free_path: RGBColor, albedo: RGBColor) -> Self {
+        let sigma_t = RGBColor::new(1., 1., 1.) / mean_free_path;
+        Self {
+            boundary,
+            surface: Dielectric::new(ir),
+            phase_function: Isotropic::new_from_color(RGBColor::new(1., 1., 1.)),
+            sigma_t,
+            sigma_s: sigma_t * albedo,
+        }
+    }
+
+    // 光在内部传播 dis 距离后各通道的衰减, 即扩散剖面
+    pub fn attenuation(&self, dis: f64) -> RGBColor {
+        RGBColor::new(
+            (-self.sigma_t.x * dis).exp(),
+            (-self.sigma_t.y * dis).exp(),
+            (-self.sigma_t.z * dis).exp(),
+        )
+    }
+}
+
+impl<TH: Hittable> Hittable for Subsurface<TH> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        let mut rec = self.boundary.hit(ray, t_min, t_max)?;
+        rec.mat = &self.surface;
+        if rec.front_face {
+            // 光线来自外侧, 在边界处折射或反射
+            return Some(rec);
+        }
+
+        // 光线位于物体内部: 随机选取一个通道采样自由程,
+        // 并以各通道概率的平均值作为采样概率 (spectral MIS)
+        let ray_len = ray.dir.length();
+        let dis_inside_bound = (rec.t - t_min) * ray_len;
+        let channel = rand::thread_rng().gen_range(0..3);
+        let hit_dis = -f64::ln(rand_1()) / self.sigma_t[channel];
+
+        if hit_dis < dis_inside_bound {
+            let tr = self.attenuation(hit_dis);
+            let pdf = (self.sigma_t * tr).average();
+
+            let mut sca_rec = HitRecord::new(
+                0.,
+                0.,
+                t_min + hit_dis / ray_len,
+                ray,
+                &(-ray.dir).to_unit(),
+                &medium_tangent(&ray.dir),
+                &self.phase_function,
+            );
+            sca_rec.weight = self.sigma_s * tr / pdf;
+            Some(sca_rec)
+        } else {
+            let tr = self.attenuation(dis_inside_bound);
+            rec.weight = rec.weight * tr / tr.average();
+            Some(rec)
+        }
+    }
+
+    fn bounding_box(&self, tm: f64, dur: f64) -> Option<AABB> {
+        self.boundary.bounding_box(tm, dur)
+    }
+
+    fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
+        self.boundary.pdf_value(orig, dir)
+    }
+
+    fn rand_dir(&self, orig: &Vec3) -> Vec3 {
+        self.boundary.rand_dir(orig)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::INFINITY;
+
+    use crate::{hittable::object::sphere::Sphere, material::lambertian::Lambertian};
+
+    use super::*;
+
+    fn test_subsurface() -> Subsurface<Sphere<Lambertian<SolidColor>>> {
+        let boundary = Sphere::new(
+            Point3::default(),
+            1.,
+            Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5)),
+        );
+        Subsurface::new(
+            boundary,
+            1.5,
+            RGBColor::new(2., 1., 0.25),
+            RGBColor::new(0.9, 0.8, 0.7),
+        )
+    }
+
+    #[test]
+    fn attenuation_profile() {
+        let sss = test_subsurface();
+        assert!((sss.attenuation(0.) - RGBColor::new(1., 1., 1.)).length() < 1e-9);
+
+        let tr = sss.attenuation(1.);
+        assert!((tr.x - (-0.5f64).exp()).abs() < 1e-9);
+        assert!((tr.y - (-1f64).exp()).abs() < 1e-9);
+        assert!((tr.z - (-4f64).exp()).abs() < 1e-9);
+        // 平均自由程越大的通道传播得越远
+        assert!(tr.x > tr.y && tr.y > tr.z);
+    }
+
+    #[test]
+    fn exit_point_sampling() {
+        // 自球心出发, 到达边界前走过距离 1
+        let sss = test_subsurface();
+        let ray = Ray::new(Point3::default(), Vec3::new(1., 0., 0.), 0.);
+
+        let n = 200000;
+        let mut exit_weight = RGBColor::default();
+        for _i in 0..n {
+            let rec = sss.hit(&ray, 0., INFINITY).unwrap();
+            if (rec.t - 1.).abs() < 1e-9 {
+                exit_weight += rec.weight;
+            } else {
+                // 散射点位于物体内部, 且散射权重为正
+                assert!(rec.t > 0. && rec.t < 1.);
+                assert!(rec.weight.x > 0. && rec.weight.y > 0. && rec.weight.z > 0.);
+            }
+        }
+
+        // 穿出边界的权重为逐通道透射率的无偏估计
+        let expected = sss.attenuation(1.);
+        let estimated = exit_weight / n as f64;
+        for c in 0..3 {
+            assert!((estimated[c] - expected[c]).abs() < 0.01);
+        }
+    }
+}
@@ -3,7 +3,7 @@ use super::super::{HitRecord, Hittable};
 use crate::{
     basic::{
         ray::Ray,
-        vec3::{Point3, Vec3},
+        vec3::{Point3, RGBColor, Vec3},
     },
     bvh::aabb::AABB,
 };
@@ -42,6 +42,15 @@ impl<TH: Hittable> Hittable for Translate<TH> {
             .map(|output_box| AABB::new(output_box.min + self.mov, output_box.max + self.mov))
     }
 
+    fn power(&self) -> f64 {
+        self.obj.power()
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> RGBColor {
+        let moved_ray = Ray::new(ray.orig - self.mov, ray.dir, ray.tm);
+        self.obj.transmittance(&moved_ray, t_min, t_max)
+    }
+
     fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
         self.obj.pdf_value(&(*orig - self.mov), dir)
     }
@@ -3,7 +3,7 @@ use super::super::{HitRecord, Hittable};
 use crate::{
     basic::{
         ray::Ray,
-        vec3::{Point3, Vec3},
+        vec3::{Point3, RGBColor, Vec3},
     },
     bvh::aabb::AABB,
 };
@@ -25,10 +25,12 @@ impl<TH: Hittable> Zoom<TH> {
 
 impl<TH: Hittable> Hittable for Zoom<TH> {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
-        let moved_ray = Ray::new(ray.orig / self.scale, ray.dir, ray.tm);
+        // 方向同样缩放, 使 t 在两个空间中保持一致
+        let moved_ray = Ray::new(ray.orig / self.scale, ray.dir / self.scale, ray.tm);
         if let Some(mut rec) = self.obj.hit(&moved_ray, t_min, t_max) {
             rec.p *= self.scale;
-            rec.set_face_normal(&moved_ray, &rec.normal.clone());
+            rec.tangent = (rec.tangent * self.scale).to_unit();
+            rec.set_face_normal(ray, &(rec.normal / self.scale).to_unit());
 
             Some(rec)
         } else {
@@ -42,6 +44,17 @@ impl<TH: Hittable> Hittable for Zoom<TH> {
             .map(|output_box| AABB::new(output_box.min * self.scale, output_box.max * self.scale))
     }
 
+    fn power(&self) -> f64 {
+        // 按三个坐标平面面积缩放的平均值近似
+        let s = self.scale;
+        self.obj.power() * ((s.x * s.y).abs() + (s.y * s.z).abs() + (s.z * s.x).abs()) / 3.
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> RGBColor {
+        let moved_ray = Ray::new(ray.orig / self.scale, ray.dir / self.scale, ray.tm);
+        self.obj.transmittance(&moved_ray, t_min, t_max)
+    }
+
     fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
         self.obj.pdf_value(&(*orig / self.scale), dir)
     }
@@ -50,3 +63,41 @@ impl<TH: Hittable> Hittable for Zoom<TH> {
         self.obj.rand_dir(&(*orig / self.scale))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f64::INFINITY;
+
+    use crate::{
+        hittable::object::sphere::Sphere, material::lambertian::Lambertian,
+        texture::solid_color::SolidColor,
+    };
+
+    use super::*;
+
+    #[test]
+    fn non_uniform_scale_hit() {
+        let sphere = Sphere::new(
+            Point3::default(),
+            1.,
+            Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5)),
+        );
+        let ellipsoid = Zoom::new(sphere, Vec3::new(2., 1., 3.));
+
+        let ray = Ray::new(Point3::new(0., 0., -10.), Vec3::new(0., 0., 1.), 0.);
+        let rec = ellipsoid.hit(&ray, 0., INFINITY).unwrap();
+        assert!((rec.t - 7.).abs() < 1e-9);
+        assert!((rec.p - Point3::new(0., 0., -3.)).length() < 1e-9);
+        assert!((rec.normal - Vec3::new(0., 0., -1.)).length() < 1e-9);
+        assert!(ellipsoid.hit(&ray, 0., 6.).is_none());
+
+        // 斜向击中时法向量垂直于椭球表面: 由 x^2 / 4 + z^2 / 9 = 1 的梯度给出
+        let ray = Ray::new(Point3::new(10., 0., 0.), Vec3::new(-1., 0., 0.2), 0.);
+        let rec = ellipsoid.hit(&ray, 0., INFINITY).unwrap();
+        let p = rec.p;
+        assert!((p.x * p.x / 4. + p.z * p.z / 9. - 1.).abs() < 1e-9);
+        let grad = Vec3::new(p.x / 4., 0., p.z / 9.).to_unit();
+        assert!((rec.normal - grad).length() < 1e-9);
+        assert!(rec.front_face);
+    }
+}
@@ -8,8 +8,9 @@ use rand::prelude::SliceRandom;
 
 use crate::{
     basic::{
+        onb::ONB,
         ray::Ray,
-        vec3::{Point3, Vec3},
+        vec3::{Point3, RGBColor, Vec3},
     },
     bvh::aabb::AABB,
     material::Material,
@@ -30,6 +31,20 @@ pub trait Hittable: Send + Sync {
         Vec3::new(1., 0., 0.)
     }
 
+    fn power(&self) -> f64 {
+        // 发光功率 (平均发光亮度 * 面积), 用于按功率采样光源
+        0.
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> RGBColor {
+        // 光线在 t_min ~ t_max 间穿过该物体的透射率, 用于阴影测试; 介质可给出非 0/1 的值
+        if self.hit(ray, t_min, t_max).is_some() {
+            RGBColor::default()
+        } else {
+            RGBColor::new(1., 1., 1.)
+        }
+    }
+
     fn map_to(value: f64, width: f64, threshold: f64) -> f64
     where
         Self: Sized,
@@ -49,6 +64,11 @@ pub struct HitRecord<'a> {
     pub front_face: bool,      // 光线是否来自外侧
     pub u: f64,                // 碰撞点对应物体的 u,v, 用于计算贴图
     pub v: f64,                //
+    pub tangent: Vec3,         // 切向量, 沿 u 增大方向, 用于构建切空间
+    pub shading_normal: Vec3,  // 着色法向量, 可被法线/凹凸贴图扰动
+    pub weight: RGBColor,      // 该次碰撞对光线颜色的额外权重, 用于介质内的逐通道采样
+    pub emission: RGBColor,    // 介质沿光线累积的自发光, 已包含采样权重
+    pub tm: f64,               // 光线的时刻, 用于随时间变化的纹理
 }
 
 impl<'a> HitRecord<'a> {
@@ -58,6 +78,7 @@ impl<'a> HitRecord<'a> {
         t: f64,
         ray: &Ray,
         outward_normal: &Vec3,
+        tangent: &Vec3,
         mat: &'a dyn Material,
     ) -> Self {
         let mut tmp_rec = Self {
@@ -68,6 +89,11 @@ impl<'a> HitRecord<'a> {
             front_face: bool::default(),
             u,
             v,
+            tangent: *tangent,
+            shading_normal: Vec3::default(),
+            weight: RGBColor::new(1., 1., 1.),
+            emission: RGBColor::default(),
+            tm: ray.tm,
         };
         tmp_rec.set_face_normal(ray, outward_normal);
 
@@ -75,13 +101,19 @@ impl<'a> HitRecord<'a> {
     }
 
     fn set_face_normal(&mut self, ray: &Ray, outward_normal: &Vec3) {
-        // 初始化 front_fase & normal
+        // 初始化 front_fase & normal & shading_normal
         self.front_face = Vec3::dot(&ray.dir, &*outward_normal) < 0.;
         self.normal = if self.front_face {
             *outward_normal
         } else {
             -*outward_normal
         };
+        self.shading_normal = self.normal;
+    }
+
+    pub fn tangent_space(&self) -> ONB {
+        // 以着色法向量为 w, 切向量为 u 的切空间
+        ONB::build_from_wu(&self.shading_normal, &self.tangent)
     }
 }
 
@@ -154,4 +186,19 @@ impl Hittable for HittableList {
             .unwrap()
             .rand_dir(orig)
     }
+
+    fn power(&self) -> f64 {
+        self.objects.iter().map(|obj| obj.power()).sum()
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> RGBColor {
+        let mut tr = RGBColor::new(1., 1., 1.);
+        for obj in &self.objects {
+            tr *= obj.transmittance(ray, t_min, t_max);
+            if tr.is_zero() {
+                break;
+            }
+        }
+        tr
+    }
 }
@@ -80,6 +80,10 @@ impl Hittable for OBJModel {
     fn bounding_box(&self, tm: f64, dur: f64) -> Option<AABB> {
         self.triangles.bounding_box(tm, dur)
     }
+
+    fn power(&self) -> f64 {
+        self.triangles.power()
+    }
 }
 
 #[cfg(test)]
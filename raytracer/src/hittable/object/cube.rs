@@ -7,6 +7,7 @@ use super::{
 
 use crate::{
     basic::{
+        clamp_oi,
         ray::Ray,
         vec3::{Point3, Vec3},
     },
@@ -86,25 +87,64 @@ impl<TM: Material + Clone> Cube<TM> {
     }
 }
 
+impl<TM: Material> Cube<TM> {
+    // 十字形展开的纹理图集, u 方向 4 格, v 方向 3 格, 自外侧看各面均为正向:
+    //         [+y]
+    //   [-x]  [+z]  [+x]  [-z]
+    //         [-y]
+    // 返回 (u, v, 切向量), side 为 sides 中的下标
+    pub fn atlas_uv(&self, side: usize, p: Point3) -> (f64, f64, Vec3) {
+        let size = self.max - self.min;
+        let x = (p.x - self.min.x) / size.x;
+        let y = (p.y - self.min.y) / size.y;
+        let z = (p.z - self.min.z) / size.z;
+
+        // (列, 行, 面内横坐标, 面内纵坐标, 横坐标增大方向)
+        let (col, row, s, t, tangent) = match side {
+            0 => (3., 1., 1. - x, y, Vec3::new(-1., 0., 0.)), // -z
+            1 => (1., 1., x, y, Vec3::new(1., 0., 0.)),       // +z
+            2 => (0., 1., z, y, Vec3::new(0., 0., 1.)),       // -x
+            3 => (2., 1., 1. - z, y, Vec3::new(0., 0., -1.)), // +x
+            4 => (1., 0., x, z, Vec3::new(1., 0., 0.)),       // -y
+            5 => (1., 2., x, 1. - z, Vec3::new(1., 0., 0.)),  // +y
+            _ => panic!("Get unexpected side in Cube::atlas_uv!"),
+        };
+        (
+            (col + clamp_oi(s, 0., 1.)) / 4.,
+            (row + clamp_oi(t, 0., 1.)) / 3.,
+            tangent,
+        )
+    }
+}
+
 impl<TM: Material> Hittable for Cube<TM> {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
         let mut hit_rec = None;
         let mut closest_so_far = t_max;
 
-        for i in &self.sides {
+        for (side, i) in self.sides.iter().enumerate() {
             if let Some(temp_hit_rec) = i.hit(ray, t_min, closest_so_far) {
                 closest_so_far = temp_hit_rec.t;
-                hit_rec = Some(temp_hit_rec);
+                hit_rec = Some((side, temp_hit_rec));
             }
         }
 
-        hit_rec
+        let (side, mut rec) = hit_rec?;
+        let (u, v, tangent) = self.atlas_uv(side, rec.p);
+        rec.u = u;
+        rec.v = v;
+        rec.tangent = tangent;
+        Some(rec)
     }
 
     fn bounding_box(&self, _tm: f64, _dur: f64) -> Option<AABB> {
         Some(AABB::new(self.min, self.max))
     }
 
+    fn power(&self) -> f64 {
+        self.sides.iter().map(|side| side.power()).sum()
+    }
+
     fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
         let mut sum = 0.;
         for obj in &self.sides {
@@ -122,3 +162,64 @@ impl<TM: Material> Hittable for Cube<TM> {
             .rand_dir(orig)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f64::INFINITY;
+
+    use crate::{material::lambertian::Lambertian, texture::solid_color::SolidColor};
+
+    use super::*;
+
+    #[test]
+    fn cube_atlas_uv() {
+        let mat = Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5));
+        let cube = Cube::new(Point3::new(0., 0., 0.), Point3::new(2., 2., 2.), mat);
+        let uv_at = |orig: Point3, dir: Vec3| {
+            let rec = cube.hit(&Ray::new(orig, dir, 0.), 0., INFINITY).unwrap();
+            (rec.u, rec.v)
+        };
+        let near = |(u, v): (f64, f64), (eu, ev): (f64, f64)| {
+            assert!(
+                (u - eu).abs() < 1e-9 && (v - ev).abs() < 1e-9,
+                "{} {}",
+                u,
+                v
+            );
+        };
+
+        // 各面中心位于十字形图集对应格子的中心
+        near(
+            uv_at(Point3::new(1., 1., 5.), Vec3::new(0., 0., -1.)),
+            (1.5 / 4., 0.5),
+        );
+        near(
+            uv_at(Point3::new(5., 1., 1.), Vec3::new(-1., 0., 0.)),
+            (2.5 / 4., 0.5),
+        );
+        near(
+            uv_at(Point3::new(1., 1., -5.), Vec3::new(0., 0., 1.)),
+            (3.5 / 4., 0.5),
+        );
+        near(
+            uv_at(Point3::new(-5., 1., 1.), Vec3::new(1., 0., 0.)),
+            (0.5 / 4., 0.5),
+        );
+        near(
+            uv_at(Point3::new(1., 5., 1.), Vec3::new(0., -1., 0.)),
+            (1.5 / 4., 2.5 / 3.),
+        );
+        near(
+            uv_at(Point3::new(1., -5., 1.), Vec3::new(0., 1., 0.)),
+            (1.5 / 4., 0.5 / 3.),
+        );
+
+        // 相邻面在公共棱上纹理坐标连续
+        let front = cube.atlas_uv(1, Point3::new(2., 0.5, 2.));
+        let right = cube.atlas_uv(3, Point3::new(2., 0.5, 2.));
+        near((front.0, front.1), (right.0, right.1));
+        let top = cube.atlas_uv(5, Point3::new(0.5, 2., 2.));
+        let front = cube.atlas_uv(1, Point3::new(0.5, 2., 2.));
+        near((front.0, front.1), (top.0, top.1));
+    }
+}
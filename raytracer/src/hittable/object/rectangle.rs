@@ -83,6 +83,9 @@ impl<TM: Material> Hittable for Rectangle<TM> {
             2 => Vec3::new(0., 1., 0.), // xz
             _ => panic!("Get unexpected dir in Rectangle::hit!"),
         };
+        // u,v 为碰撞点在长方形内的相对位置, 分别沿 dio[0], dio[1] 轴由 0 增至 1
+        let mut tangent = Vec3::default();
+        tangent[self.dio[0]] = 1.;
 
         Some(HitRecord::new(
             (b1 - a1) / (a2 - a1),
@@ -90,6 +93,7 @@ impl<TM: Material> Hittable for Rectangle<TM> {
             t,
             ray,
             &outward_normal,
+            &tangent,
             &self.mat,
         ))
     }
@@ -110,6 +114,10 @@ impl<TM: Material> Hittable for Rectangle<TM> {
         })
     }
 
+    fn power(&self) -> f64 {
+        self.mat.emitted_luminance() * self.area
+    }
+
     fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
         if let Some(rec) = self.hit(&Ray::new(*orig, *dir, 0.), INFINITESIMAL, INFINITY) {
             let dis_sqrd = rec.t.powi(2) * dir.length_squared();
@@ -228,6 +236,8 @@ impl<TM: Material> Hittable for OneWayRectangle<TM> {
             2 => Vec3::new(0., 1., 0.), // xz
             _ => panic!("Get unexpected dir in Rectangle::hit!"),
         };
+        let mut tangent = Vec3::default();
+        tangent[self.dio[0]] = 1.;
 
         if (Vec3::dot(&outward_normal, &ray.dir) > 0.) != self.face_coo_pos {
             Some(HitRecord::new(
@@ -236,6 +246,7 @@ impl<TM: Material> Hittable for OneWayRectangle<TM> {
                 t,
                 ray,
                 &outward_normal,
+                &tangent,
                 &self.mat,
             ))
         } else {
@@ -259,6 +270,10 @@ impl<TM: Material> Hittable for OneWayRectangle<TM> {
         })
     }
 
+    fn power(&self) -> f64 {
+        self.mat.emitted_luminance() * self.area
+    }
+
     fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
         if let Some(rec) = self.hit(&Ray::new(*orig, *dir, 0.), INFINITESIMAL, INFINITY) {
             let dis_sqrd = rec.t.powi(2) * dir.length_squared();
@@ -293,3 +308,21 @@ impl<TM: Material> Hittable for OneWayRectangle<TM> {
         rand_point - *orig
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{material::lambertian::Lambertian, texture::solid_color::SolidColor};
+
+    use super::*;
+
+    #[test]
+    fn rectangle_uv() {
+        let mat = Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5));
+        // xz 平面, x in [0, 4], z in [2, 4]
+        let rect = Rectangle::new(2, 0., 4., 2., 4., 1., mat);
+        let ray = Ray::new(Point3::new(1., 5., 3.5), Vec3::new(0., -1., 0.), 0.);
+        let rec = rect.hit(&ray, 0., INFINITY).unwrap();
+        assert!((rec.u - 0.25).abs() < 1e-9 && (rec.v - 0.75).abs() < 1e-9);
+        assert!((rec.tangent - Vec3::new(1., 0., 0.)).length() < 1e-9);
+    }
+}
@@ -1,4 +1,4 @@
-use std::f64::{consts::PI, INFINITY};
+use std::f64::consts::PI;
 
 use rand::{prelude::ThreadRng, Rng};
 
@@ -6,6 +6,7 @@ use super::super::{HitRecord, Hittable};
 
 use crate::{
     basic::{
+        clamp_oi,
         ray::Ray,
         vec3::{Point3, Vec3},
         INFINITESIMAL,
@@ -14,6 +15,34 @@ use crate::{
     material::Material,
 };
 
+// 圆环上的纹理坐标: u 为自 +x 轴转向 +z 轴的角度 / 2PI, v 为自内边缘 (0) 至外边缘 (1) 的径向位置
+fn ring_uv(p: &Point3, r: f64, t: f64) -> (f64, f64) {
+    let radian = f64::atan2(p.z, p.x).rem_euclid(2. * PI);
+    let dis = (p.x.powi(2) + p.z.powi(2)).sqrt();
+    (
+        radian / 2. / PI,
+        clamp_oi((dis - (r - t)) / (2. * t), 0., 1.),
+    )
+}
+
+// rand_dir 按角度与半径均匀取样整个圆环 (不考虑断开部分), 面积概率密度为 1 / (4PI * t * dis),
+// 换算为自 orig 沿 dir 方向的立体角概率密度; 未射中圆环时为 0
+fn annulus_pdf(orig: &Point3, dir: &Vec3, r: f64, t: f64) -> f64 {
+    let k = -orig.y / dir.y;
+    if k.is_nan() || k < INFINITESIMAL {
+        return 0.;
+    }
+    let p = *orig + *dir * k;
+    let dis = (p.x.powi(2) + p.z.powi(2)).sqrt();
+    if dis < r - t || dis > r + t {
+        return 0.;
+    }
+
+    let dis_sqrd = (*dir * k).length_squared();
+    let cosine = dir.y.abs() / dir.length();
+    dis_sqrd / (cosine * 4. * PI * t * dis)
+}
+
 #[derive(Clone)]
 pub struct Ring<TM>
 where
@@ -50,12 +79,14 @@ impl<TM: Material> Hittable for Ring<TM> {
             if d < self.ds_min || d > self.ds_max {
                 None
             } else {
+                let (u, v) = ring_uv(&p, self.r, self.t);
                 Some(HitRecord::new(
-                    0.,
-                    0.,
+                    u,
+                    v,
                     t,
                     ray,
                     &Vec3::new(0., 1., 0.),
+                    &Vec3::new(-p.z, 0., p.x).to_unit(),
                     &self.mat,
                 ))
             }
@@ -72,15 +103,12 @@ impl<TM: Material> Hittable for Ring<TM> {
         ))
     }
 
-    fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
-        if let Some(_hit_rec) = self.hit(&Ray::new(*orig, *dir, 0.), INFINITESIMAL, INFINITY) {
-            let cos_theta_max = (1. - self.r.powi(2) / (*orig).length_squared()).sqrt();
-            let solid_angle = 2. * PI * (1. - cos_theta_max);
+    fn power(&self) -> f64 {
+        self.mat.emitted_luminance() * PI * (self.ds_max - self.ds_min)
+    }
 
-            Self::map_to(1. / solid_angle, 20., 5.)
-        } else {
-            Self::map_to(1000., 20., 5.)
-        }
+    fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
+        annulus_pdf(orig, dir, self.r, self.t)
     }
 
     fn rand_dir(&self, orig: &Vec3) -> Vec3 {
@@ -119,6 +147,22 @@ impl<TM: Material> BrokenRing<TM> {
             point_list,
         }
     }
+
+    fn is_covered(&self, radian: f64) -> bool {
+        // 弧度 radian 处是否属于圆环未断开的部分
+        let mut cnt = 0;
+        for k in &self.point_list {
+            if radian < *k {
+                break;
+            } else {
+                cnt += 1;
+            }
+        }
+
+        cnt % 2 != 0
+            || (self.point_list.len() % 2 == 0
+                && (radian + 2. * PI < *self.point_list.last().unwrap()))
+    }
 }
 
 impl<TM: Material> Hittable for BrokenRing<TM> {
@@ -132,41 +176,15 @@ impl<TM: Material> Hittable for BrokenRing<TM> {
             if d < self.ds_min || d > self.ds_max {
                 None
             } else {
-                let mut radian = if p.x.abs() < INFINITESIMAL {
-                    if p.z.is_sign_positive() {
-                        PI / 2.
-                    } else {
-                        -PI / 2.
-                    }
-                } else if p.x.is_sign_positive() {
-                    f64::atan(p.z / p.x)
-                } else {
-                    f64::atan(p.z / p.x) + PI
-                };
-
-                if radian.is_sign_negative() {
-                    radian += 2. * PI;
-                }
-
-                let mut cnt = 0;
-                for k in &self.point_list {
-                    if radian < *k {
-                        break;
-                    } else {
-                        cnt += 1;
-                    }
-                }
-
-                if cnt % 2 != 0
-                    || (self.point_list.len() % 2 == 0
-                        && (radian + 2. * PI < *self.point_list.last().unwrap()))
-                {
+                let (u, v) = ring_uv(&p, self.r, self.t);
+                if self.is_covered(u * 2. * PI) {
                     Some(HitRecord::new(
-                        radian / 2. / PI,
-                        0.,
+                        u,
+                        v,
                         t,
                         ray,
                         &Vec3::new(0., 1., 0.),
+                        &Vec3::new(-p.z, 0., p.x).to_unit(),
                         &self.mat,
                     ))
                 } else {
@@ -186,15 +204,18 @@ impl<TM: Material> Hittable for BrokenRing<TM> {
         ))
     }
 
-    fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
-        if let Some(_hit_rec) = self.hit(&Ray::new(*orig, *dir, 0.), INFINITESIMAL, INFINITY) {
-            let cos_theta_max = (1. - self.r.powi(2) / (*orig).length_squared()).sqrt();
-            let solid_angle = 2. * PI * (1. - cos_theta_max);
+    fn power(&self) -> f64 {
+        // 按角度均匀取样估计未断开部分的比例
+        let n = 360;
+        let covered = (0..n)
+            .filter(|i| self.is_covered((*i as f64 + 0.5) / n as f64 * 2. * PI))
+            .count();
 
-            Self::map_to(1. / solid_angle, 20., 5.)
-        } else {
-            Self::map_to(1000., 20., 5.)
-        }
+        self.mat.emitted_luminance() * PI * (self.ds_max - self.ds_min) * covered as f64 / n as f64
+    }
+
+    fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
+        annulus_pdf(orig, dir, self.r, self.t)
     }
 
     fn rand_dir(&self, orig: &Vec3) -> Vec3 {
@@ -206,3 +227,59 @@ impl<TM: Material> Hittable for BrokenRing<TM> {
         p - *orig
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f64::INFINITY;
+
+    use crate::{material::lambertian::Lambertian, texture::solid_color::SolidColor};
+
+    use super::*;
+
+    #[test]
+    fn ring_uv() {
+        let mat = Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5));
+        let ring = Ring::new(10., 2., mat.clone());
+        // 角度 90° (+z 方向), 距圆心 11
+        let ray = Ray::new(Point3::new(0., 5., 11.), Vec3::new(0., -1., 0.), 0.);
+        let rec = ring.hit(&ray, 0., INFINITY).unwrap();
+        assert!((rec.u - 0.25).abs() < 1e-9 && (rec.v - 0.75).abs() < 1e-9);
+
+        // 仅 [PI, 2PI] 部分未断开
+        let broken = BrokenRing::new(10., 2., vec![PI, 2. * PI], mat);
+        assert!(broken.hit(&ray, 0., INFINITY).is_none());
+        let ray = Ray::new(Point3::new(-9., 5., -0.1), Vec3::new(0., -1., 0.), 0.);
+        let rec = broken.hit(&ray, 0., INFINITY).unwrap();
+        assert!(rec.u > 0.5 && rec.u < 0.51 && (rec.v - 0.25).abs() < 1e-3);
+    }
+
+    #[test]
+    fn ring_pdf() {
+        let mat = Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5));
+        let (r, t, h) = (10., 2., 5.);
+        let ring = Ring::new(r, t, mat.clone());
+        let broken = BrokenRing::new(r, t, vec![PI, 2. * PI], mat);
+        let orig = Point3::new(0., h, 0.);
+
+        // 未射中圆环时概率密度为 0
+        assert!(ring.pdf_value(&orig, &Vec3::new(0., -1., 0.)) == 0.);
+        assert!(ring.pdf_value(&orig, &Vec3::new(1., 1., 0.)) == 0.);
+        assert!(ring.pdf_value(&orig, &Vec3::new(1., 0., 0.)) == 0.);
+
+        // E[1 / pdf] 为圆环对 orig 所张的立体角, 轴线上有解析解
+        let solid_angle = 2.
+            * PI
+            * (h / (h * h + (r - t) * (r - t)).sqrt() - h / (h * h + (r + t) * (r + t)).sqrt());
+        let n = 100000;
+        for obj in [&ring as &dyn Hittable, &broken as &dyn Hittable] {
+            let mut sum = 0.;
+            for _i in 0..n {
+                let dir = obj.rand_dir(&orig);
+                let pdf = obj.pdf_value(&orig, &dir);
+                assert!(pdf > 0.);
+                sum += 1. / pdf;
+            }
+            assert!((sum / n as f64 / solid_angle - 1.).abs() < 0.01);
+        }
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
         clamp_hoi,
         onb::ONB,
         ray::Ray,
-        vec3::{Point3, Vec3},
+        vec3::{Point3, RGBColor, Vec3},
         INFINITESIMAL,
     },
     bvh::aabb::AABB,
@@ -30,6 +30,9 @@ impl<TM: Material> Sphere<TM> {
     }
 
     pub fn get_sphere_uv(p: Point3) -> (f64, f64) {
+        // 单位球面上的等距柱状投影 (equirectangular):
+        // u 为经度, 自 -x 方向起经 +z, +x, -z 绕 y 轴一周由 0 增至 1
+        // v 为纬度, 南极 (-y) 为 0, 北极 (+y) 为 1
         let theta = f64::acos(-p.y);
         let mut phi = f64::atan2(-p.z, p.x) + PI;
         if phi.is_sign_negative() {
@@ -41,6 +44,16 @@ impl<TM: Material> Sphere<TM> {
             clamp_hoi(theta / PI, 0., 1.),
         )
     }
+
+    pub fn get_sphere_tangent(p: Point3) -> Vec3 {
+        // 单位球面上 u (即 phi) 增大的方向, 两极处退化为 x 轴
+        let tangent = Vec3::new(p.z, 0., -p.x);
+        if tangent.is_zero() {
+            Vec3::new(1., 0., 0.)
+        } else {
+            tangent.to_unit()
+        }
+    }
 }
 
 impl<TM: Material> Hittable for Sphere<TM> {
@@ -72,12 +85,18 @@ impl<TM: Material> Hittable for Sphere<TM> {
             front_face: bool::default(),
             u: 0.,
             v: 0.,
+            tangent: Vec3::default(),
+            shading_normal: Vec3::default(),
+            weight: RGBColor::new(1., 1., 1.),
+            emission: RGBColor::default(),
+            tm: ray.tm,
         };
         let outward_normal = (rec.p - self.cen) / self.r;
         rec.set_face_normal(ray, &outward_normal);
         let uv = Self::get_sphere_uv(outward_normal);
         rec.u = uv.0;
         rec.v = uv.1;
+        rec.tangent = Self::get_sphere_tangent(outward_normal);
 
         Some(rec)
     }
@@ -89,6 +108,10 @@ impl<TM: Material> Hittable for Sphere<TM> {
         ))
     }
 
+    fn power(&self) -> f64 {
+        self.mat.emitted_luminance() * 4. * PI * self.r.powi(2)
+    }
+
     fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
         if let Some(_hit_rec) = self.hit(&Ray::new(*orig, *dir, 0.), INFINITESIMAL, INFINITY) {
             let cos_theta_max = (1. - self.r.powi(2) / (self.cen - *orig).length_squared()).sqrt();
@@ -108,3 +131,29 @@ impl<TM: Material> Hittable for Sphere<TM> {
         uvw.local(&Vec3::rand_to_sphere(self.r, dis_sqrd))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{material::lambertian::Lambertian, texture::solid_color::SolidColor};
+
+    use super::*;
+
+    #[test]
+    fn sphere_uv() {
+        let sphere = Sphere::new(
+            Point3::new(1., 2., 3.),
+            2.,
+            Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5)),
+        );
+        // 自 +z 方向射入, 碰撞点为 +z 极, 经度 0.25, 纬度 0.5
+        let ray = Ray::new(Point3::new(1., 2., 10.), Vec3::new(0., 0., -1.), 0.);
+        let rec = sphere.hit(&ray, 0., INFINITY).unwrap();
+        assert!((rec.u - 0.25).abs() < 1e-9 && (rec.v - 0.5).abs() < 1e-9);
+        assert!((rec.tangent - Vec3::new(1., 0., 0.)).length() < 1e-9);
+
+        // 自上方射入, 碰撞点为北极
+        let ray = Ray::new(Point3::new(1., 10., 3.), Vec3::new(0., -1., 0.), 0.);
+        let rec = sphere.hit(&ray, 0., INFINITY).unwrap();
+        assert!(rec.v > 1. - 1e-6);
+    }
+}
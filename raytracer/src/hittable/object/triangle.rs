@@ -24,6 +24,8 @@ where
     pub mat: TM,
     pub cen: Point3, // 三角形中心
     pub area: f64,
+    pub uv: [(f64, f64); 3], // 各顶点的纹理坐标, 默认为 (0,0) (1,0) (0,1), 即 u,v 为 ver[1], ver[2] 的重心坐标
+    tangent: Vec3,           // u 增大的方向
     v: Vec3,
     w: Vec3,
     // v, w 用于计算线面相交, 具体原理见
@@ -66,12 +68,30 @@ impl<TM: Material> Triangle<TM> {
             mat,
             area,
             cen,
+            uv: [(0., 0.), (1., 0.), (0., 1.)],
+            tangent: (ver[1] - ver[0]).to_unit(),
             v,
             w,
             ab: ver[1] - ver[0],
             ac: ver[2] - ver[0],
         }
     }
+
+    pub fn with_uv(mut self, uv: [(f64, f64); 3]) -> Self {
+        self.uv = uv;
+
+        // 由 ab = du1 * T + dv1 * B, ac = du2 * T + dv2 * B 解出 u 增大的方向 T
+        let (du1, dv1) = (uv[1].0 - uv[0].0, uv[1].1 - uv[0].1);
+        let (du2, dv2) = (uv[2].0 - uv[0].0, uv[2].1 - uv[0].1);
+        let det = du1 * dv2 - du2 * dv1;
+        let tangent = (self.ab * dv2 - self.ac * dv1) / det;
+        self.tangent = if det.abs() < INFINITESIMAL || tangent.is_zero() {
+            self.ab.to_unit()
+        } else {
+            tangent.to_unit()
+        };
+        self
+    }
 }
 
 impl<TM: Material> Hittable for Triangle<TM> {
@@ -95,7 +115,10 @@ impl<TM: Material> Hittable for Triangle<TM> {
             if beta.is_sign_positive() && beta < 1. {
                 let alpha = 1. - gamma - beta;
                 if alpha.is_sign_positive() && alpha < 1. {
-                    return Some(HitRecord::new(alpha, beta, t, ray, &n, &self.mat));
+                    // 按重心坐标插值顶点纹理坐标
+                    let u = self.uv[0].0 * alpha + self.uv[1].0 * beta + self.uv[2].0 * gamma;
+                    let v = self.uv[0].1 * alpha + self.uv[1].1 * beta + self.uv[2].1 * gamma;
+                    return Some(HitRecord::new(u, v, t, ray, &n, &self.tangent, &self.mat));
                 }
             }
         }
@@ -120,6 +143,10 @@ impl<TM: Material> Hittable for Triangle<TM> {
         Some(AABB::new(min, max))
     }
 
+    fn power(&self) -> f64 {
+        self.mat.emitted_luminance() * self.area
+    }
+
     fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
         if let Some(rec) = self.hit(&Ray::new(*orig, *dir, 0.), INFINITESIMAL, INFINITY) {
             let dis_sqrd = rec.t.powi(2) * dir.length_squared();
@@ -143,3 +170,34 @@ impl<TM: Material> Hittable for Triangle<TM> {
         (self.ab * k1 + self.ac * k2) - *orig
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{material::lambertian::Lambertian, texture::solid_color::SolidColor};
+
+    use super::*;
+
+    #[test]
+    fn triangle_uv() {
+        let mat = Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5));
+        let tri = Triangle::new(
+            [
+                Point3::new(0., 0., 0.),
+                Point3::new(2., 0., 0.),
+                Point3::new(0., 2., 0.),
+            ],
+            mat,
+        );
+        let ray = Ray::new(Point3::new(0.5, 0.3, 5.), Vec3::new(0., 0., -1.), 0.);
+
+        // 默认 u,v 为 ver[1], ver[2] 的重心坐标
+        let rec = tri.hit(&ray, 0., INFINITY).unwrap();
+        assert!((rec.u - 0.25).abs() < 1e-9 && (rec.v - 0.15).abs() < 1e-9);
+
+        // 自定义顶点纹理坐标, u 沿 -y 增大
+        let tri = tri.with_uv([(1., 0.), (1., 1.), (0., 0.)]);
+        let rec = tri.hit(&ray, 0., INFINITY).unwrap();
+        assert!((rec.u - 0.85).abs() < 1e-9 && (rec.v - 0.25).abs() < 1e-9);
+        assert!((rec.tangent - Vec3::new(0., -1., 0.)).length() < 1e-9);
+    }
+}
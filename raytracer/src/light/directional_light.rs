@@ -0,0 +1,84 @@
+use std::f64::INFINITY;
+
+use crate::{
+    background::sky::PreethamSky,
+    basic::vec3::{Point3, RGBColor, Vec3},
+};
+
+use super::{Light, LightSample};
+
+#[derive(Clone)]
+pub struct DirectionalLight {
+    pub dir: Vec3,            // 光线传播方向
+    pub irradiance: RGBColor, // 垂直于光线的平面上所受照度
+}
+
+impl DirectionalLight {
+    pub fn new(dir: Vec3, irradiance: RGBColor) -> Self {
+        Self {
+            dir: dir.to_unit(),
+            irradiance,
+        }
+    }
+
+    pub fn new_from_sky(sky: &PreethamSky) -> Self {
+        // 与天空模型中的太阳一致, 天空本身应使用 without_sun_disk 以免重复计算
+        Self {
+            dir: -sky.sun_dir,
+            irradiance: sky.sun_radiance() * sky.sun_solid_angle(),
+        }
+    }
+}
+
+impl Light for DirectionalLight {
+    fn sample(&self, _p: &Point3) -> Option<LightSample> {
+        Some(LightSample {
+            dir: -self.dir,
+            dis: INFINITY,
+            irradiance: self.irradiance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        basic::ray::Ray,
+        hittable::{object::rectangle::Rectangle, HittableList},
+        light::LightList,
+        material::lambertian::Lambertian,
+    };
+
+    use super::*;
+
+    #[test]
+    fn shadow_ray_direction() {
+        let mut lights = LightList::default();
+        lights.add(DirectionalLight::new(
+            Vec3::new(0., -2., 0.),
+            RGBColor::new(3., 3., 3.),
+        ));
+        // y = 1 平面上 x, z in [0, 2] 的遮挡物
+        let mut world = HittableList::default();
+        world.add(Rectangle::new(
+            2,
+            0.,
+            2.,
+            0.,
+            2.,
+            1.,
+            Lambertian::new_from_color(RGBColor::new(0.5, 0.5, 0.5)),
+        ));
+
+        let gather = |p: Point3| {
+            lights.gather(&p, 0., &world, None, |shadow_ray: &Ray| {
+                // 阴影光线由碰撞点指向光源, 即与光线传播方向相反
+                assert!((shadow_ray.dir - Vec3::new(0., 1., 0.)).length() < 1e-9);
+                1.
+            })
+        };
+        assert!(gather(Point3::new(1., 0., 1.)).is_zero());
+        assert!((gather(Point3::new(3., 0., 1.)).x - 3.).abs() < 1e-9);
+        assert!((gather(Point3::new(1., 2., 1.)).x - 3.).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,209 @@
+use std::fs;
+
+use crate::basic::clamp_oi;
+
+// 在升序的角度表中线性插值, 返回 (下标, 插值系数)
+fn locate(angles: &[f64], x: f64) -> Option<(usize, f64)> {
+    let n = angles.len();
+    if n == 1 {
+        return Some((0, 0.));
+    }
+    if x < angles[0] || x > angles[n - 1] {
+        return None;
+    }
+    let idx = angles.partition_point(|a| *a <= x).clamp(1, n - 1) - 1;
+    let span = angles[idx + 1] - angles[idx];
+    let k = if span > 0. {
+        (x - angles[idx]) / span
+    } else {
+        0.
+    };
+
+    Some((idx, clamp_oi(k, 0., 1.)))
+}
+
+#[derive(Clone)]
+pub struct IesProfile {
+    // IES LM-63 配光曲线, 发光强度已归一化至最大值为 1
+    pub vertical_angles: Vec<f64>,   // 单位为度, 0 为光轴方向
+    pub horizontal_angles: Vec<f64>, // 单位为度
+    pub candela: Vec<Vec<f64>>,      // candela[水平角][垂直角]
+}
+
+impl IesProfile {
+    pub fn load_from_file(file_name: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(file_name).map_err(|e| {
+            format!(
+                "Opening IES file fails! File name: \"{}\" ({})",
+                file_name, e
+            )
+        })?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        // 跳过文件头的关键字, 直至 TILT= 行
+        let mut lines = text.lines();
+        loop {
+            match lines.next() {
+                Some(line) if line.trim_start().starts_with("TILT=") => {
+                    if line.trim() != "TILT=NONE" {
+                        return Err("Only TILT=NONE IES files are supported".to_string());
+                    }
+                    break;
+                }
+                Some(_) => continue,
+                None => return Err("Missing TILT line in IES file".to_string()),
+            }
+        }
+
+        let mut nums = lines
+            .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse::<f64>()
+                    .map_err(|_| format!("Invalid number \"{}\" in IES file", s))
+            });
+        let mut next = || {
+            nums.next()
+                .unwrap_or_else(|| Err("Unexpected end of IES file".to_string()))
+        };
+
+        // 灯数, 每灯流明, 倍率, 垂直角数, 水平角数, 光度类型, 单位, 宽, 长, 高
+        let mut header = [0.; 10];
+        for h in &mut header {
+            *h = next()?;
+        }
+        // 镇流器系数, 保留项, 输入功率
+        for _ in 0..3 {
+            next()?;
+        }
+        let multiplier = header[2];
+        let n_vert = header[3] as usize;
+        let n_horiz = header[4] as usize;
+        if n_vert == 0 || n_horiz == 0 {
+            return Err("Empty angle table in IES file".to_string());
+        }
+
+        let mut vertical_angles = Vec::with_capacity(n_vert);
+        for _ in 0..n_vert {
+            vertical_angles.push(next()?);
+        }
+        let mut horizontal_angles = Vec::with_capacity(n_horiz);
+        for _ in 0..n_horiz {
+            horizontal_angles.push(next()?);
+        }
+        let mut candela = vec![vec![0.; n_vert]; n_horiz];
+        let mut max_cd: f64 = 0.;
+        for row in &mut candela {
+            for c in row.iter_mut() {
+                *c = next()? * multiplier;
+                max_cd = max_cd.max(*c);
+            }
+        }
+        if max_cd > 0. {
+            for row in &mut candela {
+                for c in row.iter_mut() {
+                    *c /= max_cd;
+                }
+            }
+        }
+
+        Ok(Self {
+            vertical_angles,
+            horizontal_angles,
+            candela,
+        })
+    }
+
+    // theta 为与光轴夹角, phi 为绕光轴的角度, 单位均为度
+    pub fn value(&self, theta: f64, phi: f64) -> f64 {
+        let (i, ki) = match locate(&self.vertical_angles, theta) {
+            Some(ret) => ret,
+            None => return 0.,
+        };
+
+        // 按最后一个水平角处理对称性
+        let last = *self.horizontal_angles.last().unwrap();
+        let phi = phi.rem_euclid(360.);
+        let phi = if last <= 0. {
+            0.
+        } else if last <= 90. {
+            let p = phi % 180.;
+            if p > 90. {
+                180. - p
+            } else {
+                p
+            }
+        } else if last <= 180. {
+            if phi > 180. {
+                360. - phi
+            } else {
+                phi
+            }
+        } else {
+            phi
+        };
+        let (j, kj) = locate(&self.horizontal_angles, phi).unwrap_or((0, 0.));
+
+        let at = |h: usize, v: usize| {
+            let h = h.min(self.horizontal_angles.len() - 1);
+            let v = v.min(self.vertical_angles.len() - 1);
+            self.candela[h][v]
+        };
+        let lower = at(j, i) * (1. - ki) + at(j, i + 1) * ki;
+        let upper = at(j + 1, i) * (1. - ki) + at(j + 1, i + 1) * ki;
+
+        lower * (1. - kj) + upper * kj
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IES_TEXT: &str = "IESNA:LM-63-2002
+[TEST] inline profile
+[MANUFAC] none
+TILT=NONE
+1 1000 2 3 2 1 2 0 0 0
+1 1 100
+0 45 90
+0 90
+100 50 0
+200, 100, 0
+";
+
+    #[test]
+    fn parse_lm63() {
+        let ies = IesProfile::parse(IES_TEXT).unwrap();
+        assert_eq!(ies.vertical_angles, vec![0., 45., 90.]);
+        assert_eq!(ies.horizontal_angles, vec![0., 90.]);
+        // 乘以倍率后归一化至最大值为 1
+        assert_eq!(ies.candela, vec![vec![0.5, 0.25, 0.], vec![1., 0.5, 0.]]);
+
+        assert!(IesProfile::parse("IESNA:LM-63-2002\n1 2 3").is_err());
+        assert!(IesProfile::parse(&IES_TEXT.replace("TILT=NONE", "TILT=INCLUDE")).is_err());
+        assert!(IesProfile::parse(&IES_TEXT.replace("200, 100, 0", "200 100")).is_err());
+        assert!(IesProfile::parse(&IES_TEXT.replace("100 50 0", "100 x 0")).is_err());
+    }
+
+    #[test]
+    fn value_interpolation() {
+        let ies = IesProfile::parse(IES_TEXT).unwrap();
+        let check = |theta: f64, phi: f64, expected: f64| {
+            assert!((ies.value(theta, phi) - expected).abs() < 1e-9);
+        };
+
+        check(0., 0., 0.5);
+        check(22.5, 0., 0.375);
+        check(45., 90., 0.5);
+        check(22.5, 45., 0.5625);
+        // 最后一个水平角为 90°, 四个象限对称
+        check(0., 135., 0.75);
+        check(0., 270., 1.);
+        check(0., -45., 0.75);
+        // 超出垂直角范围
+        check(100., 0., 0.);
+    }
+}
@@ -0,0 +1,396 @@
+use std::f64::INFINITY;
+
+use rand::Rng;
+
+use crate::{
+    basic::{
+        rand_1,
+        ray::Ray,
+        vec3::{Point3, Vec3},
+        INFINITESIMAL,
+    },
+    bvh::aabb::AABB,
+    hittable::{HitRecord, Hittable, HittableList},
+};
+
+// 各光源的采样权重: 发光功率, 不发光的物体 (如用于重要性采样的玻璃球) 取平均功率
+fn light_weights(lights: &HittableList) -> Vec<f64> {
+    let power: Vec<f64> = lights.objects.iter().map(|obj| obj.power()).collect();
+    let emissive: Vec<f64> = power.iter().copied().filter(|p| *p > 0.).collect();
+    let fallback = if emissive.is_empty() {
+        1.
+    } else {
+        emissive.iter().sum::<f64>() / emissive.len() as f64
+    };
+
+    power
+        .into_iter()
+        .map(|p| if p > 0. { p } else { fallback })
+        .collect()
+}
+
+//=================================================
+
+struct AliasTable {
+    // Vose alias method, O(1) 按权重抽样
+    prob: Vec<f64>,
+    alias: Vec<usize>,
+    pmf: Vec<f64>,
+}
+
+impl AliasTable {
+    fn new(weights: &[f64]) -> Self {
+        let n = weights.len();
+        let total: f64 = weights.iter().sum();
+        let pmf: Vec<f64> = weights.iter().map(|w| w / total).collect();
+
+        let mut prob = vec![1.; n];
+        let mut alias: Vec<usize> = (0..n).collect();
+        let mut scaled: Vec<f64> = pmf.iter().map(|p| p * n as f64).collect();
+        let mut small: Vec<usize> = (0..n).filter(|i| scaled[*i] < 1.).collect();
+        let mut large: Vec<usize> = (0..n).filter(|i| scaled[*i] >= 1.).collect();
+
+        while let (Some(s), Some(l)) = (small.pop(), large.pop()) {
+            prob[s] = scaled[s];
+            alias[s] = l;
+            scaled[l] -= 1. - scaled[s];
+            if scaled[l] < 1. {
+                small.push(l);
+            } else {
+                large.push(l);
+            }
+        }
+        // 剩余项由于浮点误差未配对, 概率均为 1
+
+        Self { prob, alias, pmf }
+    }
+
+    fn sample(&self) -> usize {
+        let i = rand::thread_rng().gen_range(0..self.prob.len());
+        if rand_1() < self.prob[i] {
+            i
+        } else {
+            self.alias[i]
+        }
+    }
+}
+
+//=================================================
+
+enum LightBvhChild {
+    Leaf(usize), // 光源下标
+    Inner(Box<LightBvhNode>, Box<LightBvhNode>),
+}
+
+struct LightBvhNode {
+    aabb_box: AABB,
+    weight: f64, // 子树内光源权重之和
+    child: LightBvhChild,
+}
+
+impl LightBvhNode {
+    fn new(mut items: Vec<(usize, AABB)>, weights: &[f64]) -> Self {
+        if items.len() == 1 {
+            let (idx, aabb_box) = items[0];
+            return Self {
+                aabb_box,
+                weight: weights[idx],
+                child: LightBvhChild::Leaf(idx),
+            };
+        }
+
+        let mut aabb_box = items[0].1;
+        for (_, b) in &items {
+            aabb_box = AABB::surrounding_box(&aabb_box, b);
+        }
+        // 沿包围盒最长轴按中心排序, 从中位数处划分
+        let extent = aabb_box.max - aabb_box.min;
+        let axis = if extent.x > extent.y && extent.x > extent.z {
+            0
+        } else if extent.y > extent.z {
+            1
+        } else {
+            2
+        };
+        items.sort_unstable_by(|a, b| {
+            let ca = a.1.min[axis] + a.1.max[axis];
+            let cb = b.1.min[axis] + b.1.max[axis];
+            ca.partial_cmp(&cb).unwrap()
+        });
+        let right_items = items.split_off(items.len() / 2);
+        let left = Self::new(items, weights);
+        let right = Self::new(right_items, weights);
+
+        Self {
+            aabb_box,
+            weight: left.weight + right.weight,
+            child: LightBvhChild::Inner(Box::new(left), Box::new(right)),
+        }
+    }
+
+    fn importance(&self, orig: &Point3) -> f64 {
+        // 权重除以到包围盒中心距离的平方, 距离不小于包围盒半径
+        let cen = (self.aabb_box.min + self.aabb_box.max) / 2.;
+        let dis_sqrd = (cen - *orig).length_squared();
+        let r_sqrd = (self.aabb_box.max - self.aabb_box.min).length_squared() / 4.;
+
+        self.weight / dis_sqrd.max(r_sqrd)
+    }
+
+    fn child_prob(left: &Self, right: &Self, orig: &Point3) -> f64 {
+        // 选择左子树的概率
+        let il = left.importance(orig);
+        let ir = right.importance(orig);
+        if il + ir > 0. {
+            il / (il + ir)
+        } else {
+            0.5
+        }
+    }
+
+    fn sample(&self, orig: &Point3) -> usize {
+        match &self.child {
+            LightBvhChild::Leaf(idx) => *idx,
+            LightBvhChild::Inner(left, right) => {
+                if rand_1() < Self::child_prob(left, right, orig) {
+                    left.sample(orig)
+                } else {
+                    right.sample(orig)
+                }
+            }
+        }
+    }
+
+    fn pdf_value(&self, lights: &HittableList, ray: &Ray) -> f64 {
+        // 只有光线穿过的包围盒内的光源才可能采样到该方向
+        match &self.child {
+            LightBvhChild::Leaf(idx) => lights.objects[*idx].pdf_value(&ray.orig, &ray.dir),
+            LightBvhChild::Inner(left, right) => {
+                let prob = Self::child_prob(left, right, &ray.orig);
+                let mut sum = 0.;
+                if prob > 0. && left.aabb_box.hit(ray, INFINITESIMAL, INFINITY) {
+                    sum += prob * left.pdf_value(lights, ray);
+                }
+                if prob < 1. && right.aabb_box.hit(ray, INFINITESIMAL, INFINITY) {
+                    sum += (1. - prob) * right.pdf_value(lights, ray);
+                }
+                sum
+            }
+        }
+    }
+}
+
+//=================================================
+
+enum SamplerMode {
+    Power(AliasTable),
+    Bvh(LightBvhNode),
+    Empty,
+}
+
+pub struct LightSampler {
+    // 按发光功率选择光源, 代替 HittableList 的均匀选择
+    pub lights: HittableList,
+    mode: SamplerMode,
+}
+
+impl LightSampler {
+    pub fn new(lights: HittableList) -> Self {
+        let mode = if lights.objects.is_empty() {
+            SamplerMode::Empty
+        } else {
+            SamplerMode::Power(AliasTable::new(&light_weights(&lights)))
+        };
+
+        Self { lights, mode }
+    }
+
+    // 光源数量很大时使用, 按距离与功率在光源 BVH 上逐层选择
+    pub fn new_bvh(lights: HittableList, tm: f64, dur: f64) -> Self {
+        let mode = if lights.objects.is_empty() {
+            SamplerMode::Empty
+        } else {
+            let items = lights
+                .objects
+                .iter()
+                .enumerate()
+                .map(|(i, obj)| {
+                    let aabb_box = obj
+                        .bounding_box(tm, dur)
+                        .unwrap_or_else(|| panic!("Light without bounding box in LightSampler!"));
+                    (i, aabb_box)
+                })
+                .collect();
+            SamplerMode::Bvh(LightBvhNode::new(items, &light_weights(&lights)))
+        };
+
+        Self { lights, mode }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lights.objects.is_empty()
+    }
+}
+
+impl Hittable for LightSampler {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+        self.lights.hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, tm: f64, dur: f64) -> Option<AABB> {
+        self.lights.bounding_box(tm, dur)
+    }
+
+    fn pdf_value(&self, orig: &Point3, dir: &Vec3) -> f64 {
+        match &self.mode {
+            SamplerMode::Power(table) => self
+                .lights
+                .objects
+                .iter()
+                .zip(&table.pmf)
+                .map(|(obj, p)| p * obj.pdf_value(orig, dir))
+                .sum(),
+            SamplerMode::Bvh(root) => root.pdf_value(&self.lights, &Ray::new(*orig, *dir, 0.)),
+            SamplerMode::Empty => 0.,
+        }
+    }
+
+    fn rand_dir(&self, orig: &Vec3) -> Vec3 {
+        match &self.mode {
+            SamplerMode::Power(table) => self.lights.objects[table.sample()].rand_dir(orig),
+            SamplerMode::Bvh(root) => self.lights.objects[root.sample(orig)].rand_dir(orig),
+            SamplerMode::Empty => Vec3::new(1., 0., 0.),
+        }
+    }
+
+    fn power(&self) -> f64 {
+        self.lights.power()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        basic::vec3::RGBColor,
+        hittable::{instance::translate::Translate, object::ring::Ring},
+        material::{diffuse_light::DiffuseLight, lambertian::Lambertian},
+        texture::solid_color::SolidColor,
+    };
+
+    use super::*;
+
+    fn ring_light(strength: f64, mov: Vec3) -> Translate<Ring<DiffuseLight<SolidColor>>> {
+        let mat = DiffuseLight::new_from_color(RGBColor::new(1., 1., 1.)).with_strength(strength);
+        Translate::new(Ring::new(2., 0.5, mat), mov)
+    }
+
+    #[test]
+    fn alias_table_distribution() {
+        let weights = [1., 2., 3., 0., 4.];
+        let table = AliasTable::new(&weights);
+        for (p, w) in table.pmf.iter().zip(&weights) {
+            assert!((p - w / 10.).abs() < 1e-9);
+        }
+
+        let n = 200000;
+        let mut cnt = [0; 5];
+        for _i in 0..n {
+            cnt[table.sample()] += 1;
+        }
+        assert_eq!(cnt[3], 0);
+        for (c, w) in cnt.iter().zip(&weights) {
+            assert!((*c as f64 / n as f64 - w / 10.).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn zero_power_light_weights() {
+        let mut lights = HittableList::default();
+        lights.add(ring_light(1., Vec3::default()));
+        lights.add(Ring::new(
+            2.,
+            0.5,
+            Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5)),
+        ));
+        lights.add(ring_light(3., Vec3::default()));
+
+        // 不发光的物体取发光物体的平均功率
+        let p = lights.objects[0].power();
+        assert!(p > 0.);
+        let weights = light_weights(&lights);
+        assert!((weights[0] - p).abs() < 1e-9);
+        assert!((weights[1] - 2. * p).abs() < 1e-9);
+        assert!((weights[2] - 3. * p).abs() < 1e-9);
+
+        // 全部不发光时均匀选择
+        let mut dark = HittableList::default();
+        dark.add(Ring::new(
+            2.,
+            0.5,
+            Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5)),
+        ));
+        dark.add(Ring::new(
+            1.,
+            0.5,
+            Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5)),
+        ));
+        assert_eq!(light_weights(&dark), vec![1., 1.]);
+    }
+
+    #[test]
+    fn light_bvh_pdf() {
+        let mut lights = HittableList::default();
+        for i in 0..4 {
+            for j in 0..4 {
+                let mov = Vec3::new(i as f64 * 6. - 9., (i + j) as f64 * 0.5, j as f64 * 6. - 9.);
+                lights.add(ring_light(1. + (i * 4 + j) as f64, mov));
+            }
+        }
+        let sampler = LightSampler::new_bvh(lights, 0., 1.);
+        let root = match &sampler.mode {
+            SamplerMode::Bvh(root) => root,
+            _ => panic!("LightSampler::new_bvh should build a light BVH"),
+        };
+
+        // 沿 BVH 逐层累乘得到各光源被选中的概率
+        fn select_prob(node: &LightBvhNode, orig: &Point3, prob: f64, ret: &mut Vec<f64>) {
+            match &node.child {
+                LightBvhChild::Leaf(idx) => ret[*idx] += prob,
+                LightBvhChild::Inner(left, right) => {
+                    let k = LightBvhNode::child_prob(left, right, orig);
+                    select_prob(left, orig, prob * k, ret);
+                    select_prob(right, orig, prob * (1. - k), ret);
+                }
+            }
+        }
+        let orig = Point3::new(0.3, 8., 0.7);
+        let mut freq = vec![0.; sampler.lights.objects.len()];
+        select_prob(root, &orig, 1., &mut freq);
+        assert!((freq.iter().sum::<f64>() - 1.).abs() < 1e-9);
+
+        // 采样频率与选择概率一致
+        let n = 400000;
+        let mut cnt = vec![0.; freq.len()];
+        for _i in 0..n {
+            cnt[root.sample(&orig)] += 1. / n as f64;
+        }
+        for (c, f) in cnt.iter().zip(&freq) {
+            assert!((c - f).abs() < 0.005);
+        }
+
+        // BVH 给出的概率密度等于各光源概率密度按选择概率的加权和
+        for _i in 0..200 {
+            let dir = sampler.rand_dir(&orig);
+            let pdf = sampler.pdf_value(&orig, &dir);
+            let expected: f64 = sampler
+                .lights
+                .objects
+                .iter()
+                .zip(&freq)
+                .map(|(obj, f)| f * obj.pdf_value(&orig, &dir))
+                .sum();
+            assert!(pdf > 0.);
+            assert!((pdf / expected - 1.).abs() < 1e-9);
+        }
+    }
+}
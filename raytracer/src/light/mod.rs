@@ -0,0 +1,94 @@
+pub mod directional_light;
+pub mod ies;
+pub mod light_sampler;
+pub mod point_light;
+pub mod spot_light;
+
+use crate::{
+    atmosphere::Atmosphere,
+    basic::{
+        ray::Ray,
+        vec3::{Point3, RGBColor, Vec3},
+        INFINITESIMAL,
+    },
+    hittable::{HitRecord, Hittable, HittableList},
+};
+
+pub struct LightSample {
+    pub dir: Vec3,            // 碰撞点指向光源的单位向量
+    pub dis: f64,             // 碰撞点到光源的距离, 平行光为无穷远
+    pub irradiance: RGBColor, // 垂直于 dir 的平面上所受照度
+}
+
+// 点光源, 聚光灯, 平行光等无体积的光源, 光线无法击中, 只能通过显式采样 (next event estimation) 计算
+pub trait Light: Send + Sync {
+    fn sample(&self, p: &Point3) -> Option<LightSample>;
+}
+
+//=================================================
+
+#[derive(Default)]
+pub struct LightList {
+    pub lights: Vec<Box<dyn Light>>,
+}
+
+impl LightList {
+    pub fn add<TL>(&mut self, light: TL)
+    where
+        TL: Light + 'static,
+    {
+        self.lights.push(Box::new(light));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    // 对所有光源做阴影测试, 返回 p 处的入射光照, 每个方向以 pdf 函数给出的值加权
+    pub fn gather<F>(
+        &self,
+        p: &Point3,
+        tm: f64,
+        world: &HittableList,
+        atmosphere: Option<&Atmosphere>,
+        pdf: F,
+    ) -> RGBColor
+    where
+        F: Fn(&Ray) -> f64,
+    {
+        let mut sum = RGBColor::default();
+        for light in &self.lights {
+            if let Some(sample) = light.sample(p) {
+                let shadow_ray = Ray::new(*p, sample.dir, tm);
+                let pdf = pdf(&shadow_ray);
+                if pdf <= 0. {
+                    continue;
+                }
+                let mut tr =
+                    world.transmittance(&shadow_ray, INFINITESIMAL, sample.dis - INFINITESIMAL);
+                if let Some(atmosphere) = atmosphere {
+                    tr *= atmosphere.transmittance(&shadow_ray, sample.dis);
+                }
+                if !tr.is_zero() {
+                    sum += sample.irradiance * tr * pdf;
+                }
+            }
+        }
+        sum
+    }
+
+    // 碰撞点处的直接光照
+    pub fn direct_light(
+        &self,
+        ray: &Ray,
+        hit_rec: &HitRecord,
+        attenuation: &RGBColor,
+        world: &HittableList,
+        atmosphere: Option<&Atmosphere>,
+    ) -> RGBColor {
+        *attenuation
+            * self.gather(&hit_rec.p, ray.tm, world, atmosphere, |shadow_ray| {
+                hit_rec.mat.scattering_pdf(ray, hit_rec, shadow_ray)
+            })
+    }
+}
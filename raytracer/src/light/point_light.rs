@@ -0,0 +1,49 @@
+use crate::basic::vec3::{Point3, RGBColor};
+
+use super::{Light, LightSample};
+
+#[derive(Clone)]
+pub struct PointLight {
+    pub pos: Point3,
+    pub intensity: RGBColor, // 发光强度
+}
+
+impl PointLight {
+    pub fn new(pos: Point3, intensity: RGBColor) -> Self {
+        Self { pos, intensity }
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let dir = self.pos - *p;
+        let dis_sqrd = dir.length_squared();
+        if dis_sqrd <= 0. {
+            return None;
+        }
+
+        Some(LightSample {
+            dir: dir.to_unit(),
+            dis: dis_sqrd.sqrt(),
+            irradiance: self.intensity / dis_sqrd,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inverse_square_falloff() {
+        let light = PointLight::new(Point3::new(0., 4., 0.), RGBColor::new(8., 8., 8.));
+        let near = light.sample(&Point3::new(0., 2., 0.)).unwrap();
+        let far = light.sample(&Point3::new(0., 0., 0.)).unwrap();
+
+        assert!((near.dis - 2.).abs() < 1e-9);
+        assert!((near.irradiance.x - 2.).abs() < 1e-9);
+        assert!((far.irradiance.x - 0.5).abs() < 1e-9);
+        assert!((far.dir - Point3::new(0., 1., 0.)).length() < 1e-9);
+        assert!(light.sample(&light.pos).is_none());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::basic::{
+    degree_to_radian,
+    vec3::{Point3, RGBColor, Vec3},
+};
+
+use super::{Light, LightSample};
+
+#[derive(Clone)]
+pub struct SpotLight {
+    pub pos: Point3,
+    pub dir: Vec3,           // 聚光方向
+    pub intensity: RGBColor, // 光轴方向上的发光强度
+    cos_inner: f64,          // 内锥角以内强度不衰减
+    cos_outer: f64,          // 外锥角以外强度为 0
+}
+
+impl SpotLight {
+    pub fn new(
+        pos: Point3,
+        dir: Vec3,
+        intensity: RGBColor,
+        inner_angle: f64, // 半角, 单位为度
+        outer_angle: f64,
+    ) -> Self {
+        Self {
+            pos,
+            dir: dir.to_unit(),
+            intensity,
+            cos_inner: f64::cos(degree_to_radian(inner_angle)),
+            cos_outer: f64::cos(degree_to_radian(outer_angle)),
+        }
+    }
+
+    fn falloff(&self, cos: f64) -> f64 {
+        if cos >= self.cos_inner {
+            1.
+        } else if cos <= self.cos_outer {
+            0.
+        } else {
+            // smoothstep
+            let k = (cos - self.cos_outer) / (self.cos_inner - self.cos_outer);
+            k * k * (3. - 2. * k)
+        }
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let dir = self.pos - *p;
+        let dis_sqrd = dir.length_squared();
+        if dis_sqrd <= 0. {
+            return None;
+        }
+        let dir = dir.to_unit();
+        let falloff = self.falloff(Vec3::dot(&-dir, &self.dir));
+        if falloff <= 0. {
+            return None;
+        }
+
+        Some(LightSample {
+            dir,
+            dis: dis_sqrd.sqrt(),
+            irradiance: self.intensity * falloff / dis_sqrd,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cone_cutoff() {
+        // 位于原点, 朝 -y 照射, 内锥角 20°, 外锥角 40°
+        let light = SpotLight::new(
+            Point3::default(),
+            Vec3::new(0., -1., 0.),
+            RGBColor::new(1., 1., 1.),
+            20.,
+            40.,
+        );
+        let at = |degree: f64| {
+            let radians = degree_to_radian(degree);
+            light.sample(&Point3::new(f64::sin(radians), -f64::cos(radians), 0.))
+        };
+
+        assert!((at(0.).unwrap().irradiance.x - 1.).abs() < 1e-9);
+        assert!((at(19.).unwrap().irradiance.x - 1.).abs() < 1e-9);
+        assert!(at(41.).is_none());
+        assert!(at(120.).is_none());
+
+        // 内外锥角之间单调递减
+        let mut last = 1.;
+        for k in 1..20 {
+            let col = at(20. + k as f64).unwrap().irradiance.x;
+            assert!(col > 0. && col < last);
+            last = col;
+        }
+    }
+}
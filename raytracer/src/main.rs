@@ -1,6 +1,9 @@
+pub mod atmosphere;
+pub mod background;
 pub mod basic;
 pub mod bvh;
 pub mod hittable;
+pub mod light;
 pub mod material;
 pub mod pdf;
 mod scene;
@@ -19,10 +22,15 @@ use std::{
 use console::style;
 use image::{ImageBuffer, RgbImage};
 use indicatif::{HumanDuration, MultiProgress, ProgressBar, ProgressStyle};
-use pdf::{hittable_pdf::HittablePDF, MixedPDF, PDF};
+use pdf::{
+    background_pdf::BackgroundPDF, hittable_pdf::HittablePDF, phase_pdf::HenyeyGreensteinPDF,
+    MixedPDF, PDF,
+};
 use rand::Rng;
 
 use crate::{
+    atmosphere::{Atmosphere, AtmosphereEvent},
+    background::{Background, SolidBackground},
     basic::{
         camera::Camera,
         ray::Ray,
@@ -30,7 +38,9 @@ use crate::{
         INFINITESIMAL,
     },
     hittable::{Hittable, HittableList},
+    light::{light_sampler::LightSampler, LightList},
     material::ScaRecData,
+    texture::registry::TextureRegistry,
 };
 
 //---------------------------------------------------------------------------------
@@ -38,48 +48,127 @@ use crate::{
 fn ray_color(
     ray: &Ray,
     world: &HittableList,
-    lights: &HittableList,
-    background: &RGBColor,
+    lights: &LightSampler,
+    punctual_lights: &LightList,
+    background: &dyn Background,
+    atmosphere: Option<&Atmosphere>,
     depth: i32,
 ) -> RGBColor {
     if depth <= 0 {
         return RGBColor::default();
     }
-    if let Some(hit_rec) = world.hit(ray, INFINITESIMAL, INFINITY) {
+    let hit = world.hit(ray, INFINITESIMAL, INFINITY);
+
+    // 场景大气: 光线在到达下一个碰撞点之前可能发生散射
+    let mut tr = RGBColor::new(1., 1., 1.);
+    if let Some(atm) = atmosphere {
+        let t_max = hit.as_ref().map_or(INFINITY, |rec| rec.t);
+        match atm.sample(ray, t_max) {
+            AtmosphereEvent::Scatter(t, weight) => {
+                let p = ray.at(t);
+                let direct = punctual_lights.gather(&p, ray.tm, world, atmosphere, |shadow_ray| {
+                    atm.phase(&ray.dir, &shadow_ray.dir)
+                });
+                // 与表面相同, 在相函数, 光源, 背景三者间混合采样, 使面光源与环境光也能被直接采样
+                let phase_pdf = Box::new(HenyeyGreensteinPDF::new(ray.dir, atm.g));
+                let light_pdf = HittablePDF::new(p, lights);
+                let background_pdf = BackgroundPDF::new(background);
+                let mixed_pdf = MixedPDF::new(phase_pdf, light_pdf, background_pdf);
+
+                let pdf_dir = mixed_pdf.generate();
+                let pdf_val = mixed_pdf.value(&pdf_dir);
+                if pdf_val <= 0. {
+                    return weight * direct;
+                }
+                let scattered = Ray::new(p, pdf_dir, ray.tm);
+                let k = atm.phase(&ray.dir, &pdf_dir) / pdf_val;
+                let indirect = ray_color(
+                    &scattered,
+                    world,
+                    lights,
+                    punctual_lights,
+                    background,
+                    atmosphere,
+                    depth - 1,
+                );
+                return weight * (direct + indirect * k);
+            }
+            AtmosphereEvent::Pass(weight) => tr = weight,
+        }
+    }
+
+    let color = if let Some(hit_rec) = hit {
         let emitted = hit_rec
             .mat
             .emitted(ray, &hit_rec, hit_rec.u, hit_rec.v, hit_rec.p);
 
-        if let Some(sca_rec) = hit_rec.mat.scatter(ray, &hit_rec) {
+        let color = if let Some(sca_rec) = hit_rec.mat.scatter(ray, &hit_rec) {
             match sca_rec.dat {
                 ScaRecData::Specular(ray) => {
-                    sca_rec.attenutaion * ray_color(&ray, world, lights, background, depth - 1)
+                    sca_rec.attenutaion
+                        * ray_color(
+                            &ray,
+                            world,
+                            lights,
+                            punctual_lights,
+                            background,
+                            atmosphere,
+                            depth - 1,
+                        )
                 }
                 ScaRecData::Pdf(pdf) => {
                     let light_pdf = HittablePDF::new(hit_rec.p, lights);
-                    let mixed_pdf = MixedPDF::new(pdf, light_pdf);
+                    let background_pdf = BackgroundPDF::new(background);
+                    let mixed_pdf = MixedPDF::new(pdf, light_pdf, background_pdf);
 
                     let pdf_dir = mixed_pdf.generate();
                     let pdf_val = mixed_pdf.value(&pdf_dir);
                     let scattered = Ray::new(hit_rec.p, pdf_dir, ray.tm);
 
-                    let k = sca_rec.attenutaion
-                        * hit_rec.mat.scattering_pdf(&ray, &hit_rec, &scattered)
-                        / pdf_val;
+                    // 光源或背景可能采样到材质概率密度为 0 的方向, 此时舍弃该样本
+                    let k = if pdf_val > 0. {
+                        sca_rec.attenutaion * hit_rec.mat.scattering_pdf(&ray, &hit_rec, &scattered)
+                            / pdf_val
+                    } else {
+                        RGBColor::default()
+                    };
+
+                    // 点光源等无法被光线击中, 直接做阴影测试计算直接光照
+                    let direct = punctual_lights.direct_light(
+                        ray,
+                        &hit_rec,
+                        &sca_rec.attenutaion,
+                        world,
+                        atmosphere,
+                    );
 
                     if k.is_zero() {
-                        emitted
+                        emitted + direct
                     } else {
-                        emitted + k * ray_color(&scattered, world, lights, background, depth - 1)
+                        emitted
+                            + direct
+                            + k * ray_color(
+                                &scattered,
+                                world,
+                                lights,
+                                punctual_lights,
+                                background,
+                                atmosphere,
+                                depth - 1,
+                            )
                     }
                 }
             }
         } else {
             emitted
-        }
+        };
+
+        hit_rec.emission + hit_rec.weight * color
     } else {
-        *background
-    }
+        background.value(&ray.dir)
+    };
+
+    tr * color
 }
 
 //---------------------------------------------------------------------------------
@@ -129,7 +218,10 @@ fn main() {
     let mut img: RgbImage = ImageBuffer::new(IMAGE_WIDTH as u32, IMAGE_HEIGHT as u32);
 
     // World
-    let background = RGBColor::new(0., 0., 0.);
+    let background: Arc<dyn Background> = Arc::new(SolidBackground::new(RGBColor::new(0., 0., 0.)));
+    let punctual_lights = Arc::new(LightList::default()); // 当前作品仅由自发光物体照明, 点光源等无体积光源在此添加
+    let atmosphere: Option<Arc<Atmosphere>> = None;
+    let textures = TextureRegistry::default(); // 各线程分别构建场景, 经由同一 registry 共享图片与具名纹理
 
     // Camera
     let look_from = Point3::new(-850., 80., 0.);
@@ -182,7 +274,12 @@ fn main() {
         // Secene
         let mut section_world = HittableList::default();
         let mut section_lights = HittableList::default();
-        scene::paper_world(&mut section_world, &mut section_lights);
+        scene::paper_world(&mut section_world, &mut section_lights, &textures);
+        let section_lights = LightSampler::new_bvh(section_lights, 0., 1.);
+
+        let section_background = background.clone();
+        let section_punctual_lights = punctual_lights.clone();
+        let section_atmosphere = atmosphere.clone();
 
         let mp = multiprogress.clone();
         let progress_bar = mp.add(ProgressBar::new(
@@ -216,7 +313,9 @@ fn main() {
                                 &ray,
                                 &section_world,
                                 &section_lights,
-                                &background,
+                                &section_punctual_lights,
+                                &*section_background,
+                                section_atmosphere.as_deref(),
                                 MAX_DEPTH,
                             );
                         }
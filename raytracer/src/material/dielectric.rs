@@ -34,16 +34,16 @@ impl Material for Dielectric {
         };
 
         let unit_dir = ray.dir.to_unit();
-        let cos_theta = f64::min(Vec3::dot(&-unit_dir, &rec.normal), 1.);
+        let cos_theta = f64::min(Vec3::dot(&-unit_dir, &rec.shading_normal), 1.);
         let sin_theta = (1. - cos_theta.powi(2)).sqrt();
 
         let dir: Vec3;
         if refraction_ratio * sin_theta > 1.   // Cannot Refract
             || Dielectric::reflectance(cos_theta, refraction_ratio) > random::<f64>()
         {
-            dir = Vec3::reflect(&unit_dir, &rec.normal);
+            dir = Vec3::reflect(&unit_dir, &rec.shading_normal);
         } else {
-            dir = Vec3::refract(&unit_dir, &rec.normal, refraction_ratio);
+            dir = Vec3::refract(&unit_dir, &rec.shading_normal, refraction_ratio);
         }
 
         Some(ScatterRecord::new_specular(
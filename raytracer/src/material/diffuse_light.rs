@@ -1,43 +1,163 @@
+use std::sync::Arc;
+
 use crate::{
     basic::{
+        clamp_oi,
+        color::blackbody,
+        onb::ONB,
         ray::Ray,
-        vec3::{Point3, RGBColor},
+        vec3::{Point3, RGBColor, Vec3},
     },
     hittable::HitRecord,
+    light::ies::IesProfile,
     texture::{solid_color::SolidColor, Texture},
 };
 
 use super::Material;
 
+#[derive(Clone)]
+pub enum EmissionProfile {
+    Lambertian,           // 各方向亮度相同
+    CosinePower(f64),     // 亮度按 cos^n 衰减, 总功率与 Lambertian 相同
+    Ies(Arc<IesProfile>), // IES 配光曲线, 光轴为表面法向量
+}
+
 #[derive(Clone)]
 pub struct DiffuseLight<TT>
 where
     TT: Texture,
 {
     emit: TT,
+    pub strength: f64,   // 发光强度, 与纹理颜色相乘
+    pub one_sided: bool, // 仅外侧发光
+    pub profile: EmissionProfile,
 }
 
 impl<TT: Texture> DiffuseLight<TT> {
     pub fn new(emit: TT) -> Self {
-        Self { emit }
+        Self {
+            emit,
+            strength: 1.,
+            one_sided: false,
+            profile: EmissionProfile::Lambertian,
+        }
+    }
+
+    pub fn with_strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    pub fn with_one_sided(mut self, one_sided: bool) -> Self {
+        self.one_sided = one_sided;
+        self
+    }
+
+    pub fn with_profile(mut self, profile: EmissionProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    fn profile_factor(&self, ray: &Ray, rec: &HitRecord) -> f64 {
+        // rec.normal 已翻转至光线一侧, 配光需以外侧法向量为光轴
+        let normal = if rec.front_face {
+            rec.normal
+        } else {
+            -rec.normal
+        };
+        let dir = -ray.dir.to_unit();
+        let cos = Vec3::dot(&dir, &normal);
+        match &self.profile {
+            EmissionProfile::Lambertian => 1.,
+            EmissionProfile::CosinePower(n) => cos.max(0.).powf(*n) * (n + 2.) / 2.,
+            EmissionProfile::Ies(ies) => {
+                let uvw = ONB::build_from_wu(&normal, &rec.tangent);
+                let theta = f64::acos(clamp_oi(cos, -1., 1.)).to_degrees();
+                let phi = f64::atan2(Vec3::dot(&dir, &uvw.v()), Vec3::dot(&dir, &uvw.u()));
+                ies.value(theta, phi.to_degrees())
+            }
+        }
     }
 }
 
 impl DiffuseLight<SolidColor> {
     pub fn new_from_color(color_value: RGBColor) -> Self {
-        Self {
-            emit: SolidColor::new(color_value),
-        }
+        Self::new(SolidColor::new(color_value))
+    }
+
+    pub fn new_from_temperature(kelvin: f64, strength: f64) -> Self {
+        // 黑体辐射颜色, 亮度为 strength
+        Self::new_from_color(blackbody(kelvin)).with_strength(strength)
     }
 }
 
 impl<TT: Texture> Material for DiffuseLight<TT> {
-    fn emitted(&self, _ray: &Ray, _rec: &HitRecord, u: f64, v: f64, p: Point3) -> RGBColor {
-        self.emit.value(u, v, p)
-        // if rec.front_face {
-        //     self.emit.value(u, v, p)
-        // } else {
-        //     Vec3::default()
-        // }
+    fn emitted(&self, ray: &Ray, rec: &HitRecord, u: f64, v: f64, p: Point3) -> RGBColor {
+        if self.one_sided && !rec.front_face {
+            return RGBColor::default();
+        }
+        self.emit.value(u, v, p, rec.normal, ray.tm) * self.strength * self.profile_factor(ray, rec)
+    }
+
+    fn emitted_luminance(&self) -> f64 {
+        // 在 u,v 平面上均匀取样估计纹理的平均亮度;
+        // 取样时 p, normal 与时刻均取默认值, 即假定纹理不随位置与时间变化, 否则仅为粗略估计
+        let n = 8;
+        let mut sum = 0.;
+        for i in 0..n {
+            for j in 0..n {
+                let u = (i as f64 + 0.5) / n as f64;
+                let v = (j as f64 + 0.5) / n as f64;
+                sum += self
+                    .emit
+                    .value(u, v, Point3::default(), Vec3::default(), 0.)
+                    .luminance();
+            }
+        }
+        let sides = if self.one_sided { 0.5 } else { 1. };
+        sum / (n * n) as f64 * self.strength * sides
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::INFINITY;
+
+    use crate::hittable::{object::rectangle::Rectangle, Hittable};
+
+    use super::*;
+
+    fn emitted_from<TT: Texture>(light: DiffuseLight<TT>, y: f64) -> f64 {
+        // xz 平面, 外侧法向量为 +y, 光线沿法向量方向射入
+        let rect = Rectangle::new(2, 0., 2., 0., 2., 0., light);
+        let ray = Ray::new(Point3::new(1., y, 1.), Vec3::new(0., -y, 0.), 0.);
+        let rec = rect.hit(&ray, 0., INFINITY).unwrap();
+        rec.mat.emitted(&ray, &rec, rec.u, rec.v, rec.p).x
+    }
+
+    #[test]
+    fn profile_uses_outward_normal() {
+        let white = RGBColor::new(1., 1., 1.);
+        let cosine =
+            || DiffuseLight::new_from_color(white).with_profile(EmissionProfile::CosinePower(1.));
+        assert!((emitted_from(cosine(), 5.) - 1.5).abs() < 1e-9);
+        assert!(emitted_from(cosine(), -5.).abs() < 1e-9);
+
+        // 仅向背面发光的配光曲线
+        let ies = IesProfile {
+            vertical_angles: vec![0., 90., 180.],
+            horizontal_angles: vec![0.],
+            candela: vec![vec![0., 0., 1.]],
+        };
+        let ies = || {
+            DiffuseLight::new_from_color(white)
+                .with_profile(EmissionProfile::Ies(Arc::new(ies.clone())))
+        };
+        assert!(emitted_from(ies(), 5.).abs() < 1e-9);
+        assert!((emitted_from(ies(), -5.) - 1.).abs() < 1e-9);
+
+        let lambertian = || DiffuseLight::new_from_color(white);
+        assert!((emitted_from(lambertian(), -5.) - 1.).abs() < 1e-9);
+        assert!(emitted_from(lambertian().with_one_sided(true), -5.).abs() < 1e-9);
     }
 }
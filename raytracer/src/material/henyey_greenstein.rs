@@ -0,0 +1,51 @@
+use crate::{
+    basic::{
+        clamp_oi,
+        ray::Ray,
+        vec3::{RGBColor, Vec3},
+    },
+    hittable::HitRecord,
+    pdf::phase_pdf::{henyey_greenstein, HenyeyGreensteinPDF},
+    texture::{solid_color::SolidColor, Texture},
+};
+
+use super::{Material, ScatterRecord};
+
+#[derive(Clone)]
+pub struct HenyeyGreenstein<TT>
+// 各向异性相函数, 用于介质: g > 0 前向散射 (雾), g < 0 后向散射 (尘埃)
+where
+    TT: Texture,
+{
+    albedo: TT,
+    pub g: f64,
+}
+
+impl<TT: Texture> HenyeyGreenstein<TT> {
+    pub fn new(albedo: TT, g: f64) -> Self {
+        Self {
+            albedo,
+            g: clamp_oi(g, -0.999, 0.999),
+        }
+    }
+}
+
+impl HenyeyGreenstein<SolidColor> {
+    pub fn new_from_color(color_value: RGBColor, g: f64) -> Self {
+        Self::new(SolidColor::new(color_value), g)
+    }
+}
+
+impl<TT: Texture> Material for HenyeyGreenstein<TT> {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        Some(ScatterRecord::new_not_specular(
+            HenyeyGreensteinPDF::new(ray.dir, self.g),
+            self.albedo.value(rec.u, rec.v, rec.p, rec.normal, rec.tm),
+        ))
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, _hit_rec: &HitRecord, scattered: &Ray) -> f64 {
+        let cos = Vec3::dot(&ray.dir.to_unit(), &scattered.dir.to_unit());
+        henyey_greenstein(cos, self.g)
+    }
+}
@@ -1,10 +1,9 @@
+use std::f64::consts::PI;
+
 use crate::{
-    basic::{
-        ray::Ray,
-        vec3::{RGBColor, Vec3},
-    },
+    basic::{ray::Ray, vec3::RGBColor},
     hittable::HitRecord,
-    pdf::cos_pdf::CosinePDF,
+    pdf::phase_pdf::HenyeyGreensteinPDF,
     texture::{solid_color::SolidColor, Texture},
 };
 
@@ -34,14 +33,15 @@ impl Isotropic<SolidColor> {
 }
 
 impl<TT: Texture> Material for Isotropic<TT> {
-    fn scatter(&self, _ray: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+    fn scatter(&self, ray: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
         Some(ScatterRecord::new_not_specular(
-            CosinePDF::new(Vec3::rand_unit_sphere()),
-            self.albedo.value(rec.u, rec.v, rec.p),
+            HenyeyGreensteinPDF::new(ray.dir, 0.),
+            self.albedo.value(rec.u, rec.v, rec.p, rec.normal, rec.tm),
         ))
     }
 
     fn scattering_pdf(&self, _ray: &Ray, _hit_rec: &HitRecord, _scattered: &Ray) -> f64 {
-        1.
+        // 球面上的均匀分布
+        1. / (4. * PI)
     }
 }
@@ -38,13 +38,14 @@ impl Lambertian<SolidColor> {
 impl<TT: Texture> Material for Lambertian<TT> {
     fn scatter(&self, _ray: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
         Some(ScatterRecord::new_not_specular(
-            CosinePDF::new(hit_rec.normal),
-            self.albedo.value(hit_rec.u, hit_rec.v, hit_rec.p),
+            CosinePDF::new(hit_rec.shading_normal),
+            self.albedo
+                .value(hit_rec.u, hit_rec.v, hit_rec.p, hit_rec.normal, hit_rec.tm),
         ))
     }
 
     fn scattering_pdf(&self, _ray: &Ray, hit_rec: &HitRecord, scattered: &Ray) -> f64 {
-        let cosine = Vec3::dot(&hit_rec.normal, &scattered.dir.to_unit());
+        let cosine = Vec3::dot(&hit_rec.shading_normal, &scattered.dir.to_unit());
         if cosine.is_sign_negative() {
             0.
         } else {
@@ -31,7 +31,7 @@ impl Metal {
 
 impl Material for Metal {
     fn scatter(&self, ray: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
-        let reflected = Vec3::reflect(&ray.dir.to_unit(), &rec.normal);
+        let reflected = Vec3::reflect(&ray.dir.to_unit(), &rec.shading_normal);
 
         Some(ScatterRecord::new_specular(
             Ray::new(
@@ -67,7 +67,7 @@ impl<TT: Texture> ColoredMetal<TT> {
 impl<TT: Texture> Material for ColoredMetal<TT> {
     fn scatter(&self, ray: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
         if rand_1() < 0.25 {
-            let reflected = Vec3::reflect(&ray.dir.to_unit(), &hit_rec.normal);
+            let reflected = Vec3::reflect(&ray.dir.to_unit(), &hit_rec.shading_normal);
 
             Some(ScatterRecord::new_specular(
                 Ray::new(
@@ -79,14 +79,15 @@ impl<TT: Texture> Material for ColoredMetal<TT> {
             ))
         } else {
             Some(ScatterRecord::new_not_specular(
-                CosinePDF::new(hit_rec.normal),
-                self.tex.value(hit_rec.u, hit_rec.v, hit_rec.p),
+                CosinePDF::new(hit_rec.shading_normal),
+                self.tex
+                    .value(hit_rec.u, hit_rec.v, hit_rec.p, hit_rec.normal, hit_rec.tm),
             ))
         }
     }
 
     fn scattering_pdf(&self, _ray: &Ray, hit_rec: &HitRecord, scattered: &Ray) -> f64 {
-        let cosine = Vec3::dot(&hit_rec.normal, &scattered.dir.to_unit());
+        let cosine = Vec3::dot(&hit_rec.shading_normal, &scattered.dir.to_unit());
         if cosine.is_sign_negative() {
             0.
         } else {
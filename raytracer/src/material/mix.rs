@@ -0,0 +1,172 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    basic::{
+        clamp_oi,
+        ray::Ray,
+        vec3::{Point3, RGBColor, Vec3},
+    },
+    hittable::HitRecord,
+    texture::Texture,
+};
+
+use super::{Material, ScatterRecord};
+
+#[derive(Clone, Copy)]
+pub enum MixMode {
+    Threshold(f64), // 权重不低于阈值处使用 mat2, 否则使用 mat1
+    Stochastic,     // 以权重为概率使用 mat2
+}
+
+#[derive(Clone)]
+pub struct MixMaterial<TM1, TM2, TT>
+where
+    TM1: Material,
+    TM2: Material,
+    TT: Texture,
+{
+    // 以纹理亮度为权重混合两种材质, 权重 0 为 mat1, 权重 1 为 mat2
+    pub mat1: TM1,
+    pub mat2: TM2,
+    pub mask: TT,
+    pub mode: MixMode,
+}
+
+impl<TM1: Material, TM2: Material, TT: Texture> MixMaterial<TM1, TM2, TT> {
+    pub fn new(mat1: TM1, mat2: TM2, mask: TT, mode: MixMode) -> Self {
+        Self {
+            mat1,
+            mat2,
+            mask,
+            mode,
+        }
+    }
+
+    fn weight(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> f64 {
+        clamp_oi(self.mask.value(u, v, p, normal, tm).luminance(), 0., 1.)
+    }
+
+    fn use_mat2(&self, ray: &Ray, hit_rec: &HitRecord) -> bool {
+        let w = self.weight(hit_rec.u, hit_rec.v, hit_rec.p, hit_rec.normal, hit_rec.tm);
+        match self.mode {
+            MixMode::Threshold(threshold) => w >= threshold,
+            MixMode::Stochastic => hashed_rand(ray, hit_rec) < w,
+        }
+    }
+}
+
+// 由光线与碰撞点生成确定的伪随机数,
+// 保证同一次碰撞的 scatter 与 scattering_pdf 选择同一材质
+fn hashed_rand(ray: &Ray, hit_rec: &HitRecord) -> f64 {
+    let mut hasher = DefaultHasher::new();
+    for k in &[
+        hit_rec.p.x,
+        hit_rec.p.y,
+        hit_rec.p.z,
+        ray.dir.x,
+        ray.dir.y,
+        ray.dir.z,
+    ] {
+        k.to_bits().hash(&mut hasher);
+    }
+    (hasher.finish() >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+impl<TM1: Material, TM2: Material, TT: Texture> Material for MixMaterial<TM1, TM2, TT> {
+    fn emitted(&self, ray: &Ray, hit_rec: &HitRecord, u: f64, v: f64, p: Point3) -> RGBColor {
+        match self.mode {
+            MixMode::Threshold(_) => {
+                if self.use_mat2(ray, hit_rec) {
+                    self.mat2.emitted(ray, hit_rec, u, v, p)
+                } else {
+                    self.mat1.emitted(ray, hit_rec, u, v, p)
+                }
+            }
+            MixMode::Stochastic => {
+                let w = self.weight(u, v, p, hit_rec.normal, ray.tm);
+                self.mat1.emitted(ray, hit_rec, u, v, p) * (1. - w)
+                    + self.mat2.emitted(ray, hit_rec, u, v, p) * w
+            }
+        }
+    }
+
+    fn scatter(&self, ray: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
+        if self.use_mat2(ray, hit_rec) {
+            self.mat2.scatter(ray, hit_rec)
+        } else {
+            self.mat1.scatter(ray, hit_rec)
+        }
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, hit_rec: &HitRecord, scattered: &Ray) -> f64 {
+        if self.use_mat2(ray, hit_rec) {
+            self.mat2.scattering_pdf(ray, hit_rec, scattered)
+        } else {
+            self.mat1.scattering_pdf(ray, hit_rec, scattered)
+        }
+    }
+
+    fn emitted_luminance(&self) -> f64 {
+        (self.mat1.emitted_luminance() + self.mat2.emitted_luminance()) / 2.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::INFINITY;
+
+    use crate::{
+        hittable::{object::rectangle::Rectangle, Hittable},
+        material::{diffuse_light::DiffuseLight, lambertian::Lambertian},
+        texture::solid_color::SolidColor,
+    };
+
+    use super::*;
+
+    #[test]
+    fn single_material_at_extremes() {
+        let col1 = RGBColor::new(0.8, 0.1, 0.1);
+        let col2 = RGBColor::new(0.1, 0.1, 0.8);
+        for &mode in &[MixMode::Threshold(0.5), MixMode::Stochastic] {
+            for &(w, expected) in &[(0., col1), (1., col2)] {
+                let mat = MixMaterial::new(
+                    Lambertian::new_from_color(col1),
+                    Lambertian::new_from_color(col2),
+                    SolidColor::new_from_value(w, w, w),
+                    mode,
+                );
+                let rect = Rectangle::new(2, 0., 2., 0., 2., 0., mat);
+                for i in 0..100 {
+                    // 不同的入射光线不会改变所选材质
+                    let x = 0.01 + 0.014 * i as f64;
+                    let ray = Ray::new(Point3::new(x, 5., 1.), Vec3::new(0.1, -1., 0.), 0.);
+                    let rec = rect.hit(&ray, 0., INFINITY).unwrap();
+                    let sca = rec.mat.scatter(&ray, &rec).unwrap();
+                    assert_eq!(sca.attenutaion, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn emission_at_extremes() {
+        let light = RGBColor::new(4., 4., 4.);
+        for &mode in &[MixMode::Threshold(0.5), MixMode::Stochastic] {
+            for &(w, expected) in &[(0., RGBColor::default()), (1., light)] {
+                let mat = MixMaterial::new(
+                    Lambertian::new_from_color(RGBColor::new(0.5, 0.5, 0.5)),
+                    DiffuseLight::new_from_color(light),
+                    SolidColor::new_from_value(w, w, w),
+                    mode,
+                );
+                let rect = Rectangle::new(2, 0., 2., 0., 2., 0., mat);
+                let ray = Ray::new(Point3::new(1., 5., 1.), Vec3::new(0., -1., 0.), 0.);
+                let rec = rect.hit(&ray, 0., INFINITY).unwrap();
+                assert_eq!(rec.mat.emitted(&ray, &rec, rec.u, rec.v, rec.p), expected);
+            }
+        }
+    }
+}
@@ -1,8 +1,12 @@
 pub mod dielectric;
 pub mod diffuse_light;
+pub mod henyey_greenstein;
 pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
+pub mod mix;
+pub mod normal_map;
+pub mod two_sided;
 
 use crate::{
     basic::{
@@ -10,7 +14,7 @@ use crate::{
         vec3::{Point3, RGBColor},
     },
     hittable::HitRecord,
-    pdf::cos_pdf::CosinePDF,
+    pdf::PDF,
 };
 
 pub trait Material: Send + Sync {
@@ -25,6 +29,11 @@ pub trait Material: Send + Sync {
     fn scattering_pdf(&self, _ray: &Ray, _hit_rec: &HitRecord, _scattered: &Ray) -> f64 {
         0.
     }
+
+    fn emitted_luminance(&self) -> f64 {
+        // 表面平均发光亮度, 用于按功率采样光源
+        0.
+    }
 }
 
 //=================================================
@@ -36,7 +45,7 @@ pub struct ScatterRecord {
 
 pub enum ScaRecData {
     Specular(Ray),
-    Pdf(CosinePDF),
+    Pdf(Box<dyn PDF>),
 }
 
 impl ScatterRecord {
@@ -47,9 +56,12 @@ impl ScatterRecord {
         }
     }
 
-    pub fn new_not_specular(pdf: CosinePDF, attenutaion: RGBColor) -> Self {
+    pub fn new_not_specular<TP>(pdf: TP, attenutaion: RGBColor) -> Self
+    where
+        TP: PDF + 'static,
+    {
         Self {
-            dat: ScaRecData::Pdf(pdf),
+            dat: ScaRecData::Pdf(Box::new(pdf)),
             attenutaion,
         }
     }
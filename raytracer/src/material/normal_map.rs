@@ -0,0 +1,222 @@
+use crate::{
+    basic::{
+        ray::Ray,
+        vec3::{Point3, RGBColor, Vec3},
+    },
+    hittable::HitRecord,
+    texture::Texture,
+};
+
+use super::{Material, ScaRecData, ScatterRecord};
+
+// 以扰动后的着色法向量替换 hit_rec 中的 shading_normal, 几何法向量保持不变
+fn perturbed<'a>(hit_rec: &HitRecord<'a>, shading_normal: Vec3) -> HitRecord<'a> {
+    let mut rec = hit_rec.clone();
+    if !shading_normal.is_zero() && !shading_normal.is_nan() {
+        rec.shading_normal = shading_normal.to_unit();
+    }
+    rec
+}
+
+// 出射方向相对几何法向量与着色法向量位于不同侧时会造成漏光, 需舍弃
+fn is_leaking(hit_rec: &HitRecord, dir: &Vec3) -> bool {
+    let geometric = Vec3::dot(dir, &hit_rec.normal);
+    let shading = Vec3::dot(dir, &hit_rec.shading_normal);
+    geometric.is_sign_negative() != shading.is_sign_negative()
+}
+
+fn scatter_without_leak(
+    mat: &dyn Material,
+    ray: &Ray,
+    hit_rec: &HitRecord,
+) -> Option<ScatterRecord> {
+    let sca_rec = mat.scatter(ray, hit_rec)?;
+    if let ScaRecData::Specular(scattered) = &sca_rec.dat {
+        if is_leaking(hit_rec, &scattered.dir) {
+            return None;
+        }
+    }
+    Some(sca_rec)
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct NormalMap<TM, TT>
+where
+    TM: Material,
+    TT: Texture,
+{
+    // 切空间法线贴图, 颜色 (r,g,b) 对应切空间法向量 (2r-1, 2g-1, 2b-1)
+    pub mat: TM,
+    pub map: TT,
+    pub strength: f64, // 扰动强度, 1 为贴图原始法向量
+}
+
+impl<TM: Material, TT: Texture> NormalMap<TM, TT> {
+    pub fn new(mat: TM, map: TT, strength: f64) -> Self {
+        Self { mat, map, strength }
+    }
+
+    fn shading_normal(&self, hit_rec: &HitRecord) -> Vec3 {
+        let col = self
+            .map
+            .value(hit_rec.u, hit_rec.v, hit_rec.p, hit_rec.normal, hit_rec.tm);
+        let mut n = col * 2. - 1.;
+        n.x *= self.strength;
+        n.y *= self.strength;
+
+        hit_rec.tangent_space().local(&n)
+    }
+}
+
+impl<TM: Material, TT: Texture> Material for NormalMap<TM, TT> {
+    fn emitted(&self, ray: &Ray, hit_rec: &HitRecord, u: f64, v: f64, p: Point3) -> RGBColor {
+        self.mat.emitted(ray, hit_rec, u, v, p)
+    }
+
+    fn scatter(&self, ray: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
+        let rec = perturbed(hit_rec, self.shading_normal(hit_rec));
+        scatter_without_leak(&self.mat, ray, &rec)
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, hit_rec: &HitRecord, scattered: &Ray) -> f64 {
+        let rec = perturbed(hit_rec, self.shading_normal(hit_rec));
+        if is_leaking(&rec, &scattered.dir) {
+            0.
+        } else {
+            self.mat.scattering_pdf(ray, &rec, scattered)
+        }
+    }
+
+    fn emitted_luminance(&self) -> f64 {
+        self.mat.emitted_luminance()
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct BumpMap<TM, TT>
+where
+    TM: Material,
+    TT: Texture,
+{
+    // 高度贴图, 以纹理亮度为高度, 按 u,v 方向的差分扰动法向量
+    pub mat: TM,
+    pub height: TT,
+    pub scale: f64, // 高度缩放
+    pub delta: f64, // 差分步长 (u,v 空间)
+}
+
+impl<TM: Material, TT: Texture> BumpMap<TM, TT> {
+    pub fn new(mat: TM, height: TT, scale: f64) -> Self {
+        Self {
+            mat,
+            height,
+            scale,
+            delta: 0.001,
+        }
+    }
+
+    fn shading_normal(&self, hit_rec: &HitRecord) -> Vec3 {
+        let (u, v, p, n, tm) = (hit_rec.u, hit_rec.v, hit_rec.p, hit_rec.normal, hit_rec.tm);
+        let h = self.height.value(u, v, p, n, tm).luminance();
+        let dh_du = (self.height.value(u + self.delta, v, p, n, tm).luminance() - h) / self.delta;
+        let dh_dv = (self.height.value(u, v + self.delta, p, n, tm).luminance() - h) / self.delta;
+
+        hit_rec
+            .tangent_space()
+            .local(&Vec3::new(-dh_du * self.scale, -dh_dv * self.scale, 1.))
+    }
+}
+
+impl<TM: Material, TT: Texture> Material for BumpMap<TM, TT> {
+    fn emitted(&self, ray: &Ray, hit_rec: &HitRecord, u: f64, v: f64, p: Point3) -> RGBColor {
+        self.mat.emitted(ray, hit_rec, u, v, p)
+    }
+
+    fn scatter(&self, ray: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
+        let rec = perturbed(hit_rec, self.shading_normal(hit_rec));
+        scatter_without_leak(&self.mat, ray, &rec)
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, hit_rec: &HitRecord, scattered: &Ray) -> f64 {
+        let rec = perturbed(hit_rec, self.shading_normal(hit_rec));
+        if is_leaking(&rec, &scattered.dir) {
+            0.
+        } else {
+            self.mat.scattering_pdf(ray, &rec, scattered)
+        }
+    }
+
+    fn emitted_luminance(&self) -> f64 {
+        self.mat.emitted_luminance()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::INFINITY;
+
+    use crate::{
+        hittable::{instance::rotate::Rotate, object::rectangle::Rectangle, Hittable},
+        material::lambertian::Lambertian,
+        texture::{
+            gradient::{Gradient, GradientInput},
+            solid_color::SolidColor,
+        },
+    };
+
+    use super::*;
+
+    fn rotated_rect(dir: u32) -> Rotate<Rectangle<Lambertian<SolidColor>>> {
+        let mat = Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5));
+        // xz 平面, 切向量为 +x, 法向量为 +y
+        Rotate::new(Rectangle::new(2, -2., 2., -2., 2., 0., mat), dir, 55.)
+    }
+
+    #[test]
+    fn tangent_space_under_rotation() {
+        for dir in 0..3 {
+            let rect = rotated_rect(dir);
+            let down = Vec3::new(0., -1., 0.);
+            let rec = rect
+                .hit(&Ray::new(Point3::new(0.1, 5., 0.2), down, 0.), 0., INFINITY)
+                .unwrap();
+            let rec2 = rect
+                .hit(
+                    &Ray::new(Point3::new(-0.3, 5., 0.4), down, 0.),
+                    0.,
+                    INFINITY,
+                )
+                .unwrap();
+
+            // 几何法向量垂直于平面, 切向量为单位向量且位于平面内
+            assert!(Vec3::dot(&(rec2.p - rec.p), &rec.normal).abs() < 1e-9);
+            assert!((rec.tangent.length() - 1.).abs() < 1e-9);
+            assert!(Vec3::dot(&rec.tangent, &rec.normal).abs() < 1e-9);
+
+            // 平坦法线贴图不改变法向量, 切空间 +x 对应切向量
+            let mat = Lambertian::new(SolidColor::new_from_value(0.5, 0.5, 0.5));
+            let flat = NormalMap::new(mat.clone(), SolidColor::new_from_value(0.5, 0.5, 1.), 1.);
+            assert!((flat.shading_normal(&rec).to_unit() - rec.normal).length() < 1e-9);
+            let tilted = NormalMap::new(mat.clone(), SolidColor::new_from_value(1., 0.5, 0.5), 1.);
+            assert!((tilted.shading_normal(&rec).to_unit() - rec.tangent).length() < 1e-9);
+
+            // 高度沿 u 增大时, 法向量向 -u 方向倾斜
+            let height = Gradient::new(
+                vec![RGBColor::new(0., 0., 0.), RGBColor::new(1., 1., 1.)],
+                vec![0., 1.],
+            )
+            .unwrap()
+            .with_input(GradientInput::U)
+            .unwrap();
+            let bump = BumpMap::new(mat, height, 0.1);
+            let n = bump.shading_normal(&rec).to_unit();
+            assert!(Vec3::dot(&n, &rec.tangent) < -1e-3);
+            assert!(Vec3::dot(&n, &rec.normal) > 0.);
+            assert!(Vec3::dot(&n, &Vec3::cross(&rec.normal, &rec.tangent)).abs() < 1e-9);
+        }
+    }
+}
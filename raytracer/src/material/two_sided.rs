@@ -0,0 +1,180 @@
+use std::f64::consts::PI;
+
+use crate::{
+    basic::{
+        ray::Ray,
+        vec3::{Point3, RGBColor, Vec3},
+    },
+    hittable::HitRecord,
+    pdf::cos_pdf::CosinePDF,
+    texture::{solid_color::SolidColor, Texture},
+};
+
+use super::{Material, ScatterRecord};
+
+#[derive(Clone)]
+pub struct TwoSided<TM1, TM2>
+where
+    TM1: Material,
+    TM2: Material,
+{
+    // 依据光线来自外侧 (front_face) 与否, 分别使用 front & back 材质
+    pub front: TM1,
+    pub back: TM2,
+}
+
+impl<TM1: Material, TM2: Material> TwoSided<TM1, TM2> {
+    pub fn new(front: TM1, back: TM2) -> Self {
+        Self { front, back }
+    }
+}
+
+impl<TM1: Material, TM2: Material> Material for TwoSided<TM1, TM2> {
+    fn emitted(&self, ray: &Ray, hit_rec: &HitRecord, u: f64, v: f64, p: Point3) -> RGBColor {
+        if hit_rec.front_face {
+            self.front.emitted(ray, hit_rec, u, v, p)
+        } else {
+            self.back.emitted(ray, hit_rec, u, v, p)
+        }
+    }
+
+    fn scatter(&self, ray: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
+        if hit_rec.front_face {
+            self.front.scatter(ray, hit_rec)
+        } else {
+            self.back.scatter(ray, hit_rec)
+        }
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, hit_rec: &HitRecord, scattered: &Ray) -> f64 {
+        if hit_rec.front_face {
+            self.front.scattering_pdf(ray, hit_rec, scattered)
+        } else {
+            self.back.scattering_pdf(ray, hit_rec, scattered)
+        }
+    }
+
+    fn emitted_luminance(&self) -> f64 {
+        (self.front.emitted_luminance() + self.back.emitted_luminance()) / 2.
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct Translucent<TT>
+where
+    TT: Texture,
+{
+    // 薄表面半透明材质 (纸张, 灯罩), 漫反射能量中 trans 的部分透射至背面
+    pub albedo: TT,
+    pub trans: f64,
+}
+
+impl<TT: Texture> Translucent<TT> {
+    pub fn new(albedo: TT, trans: f64) -> Self {
+        Self { albedo, trans }
+    }
+}
+
+impl Translucent<SolidColor> {
+    pub fn new_from_color(color_value: RGBColor, trans: f64) -> Self {
+        Self {
+            albedo: SolidColor::new(color_value),
+            trans,
+        }
+    }
+}
+
+impl<TT: Texture> Material for Translucent<TT> {
+    fn scatter(&self, _ray: &Ray, hit_rec: &HitRecord) -> Option<ScatterRecord> {
+        Some(ScatterRecord::new_not_specular(
+            CosinePDF::new_two_sided(hit_rec.shading_normal, self.trans),
+            self.albedo
+                .value(hit_rec.u, hit_rec.v, hit_rec.p, hit_rec.normal, hit_rec.tm),
+        ))
+    }
+
+    fn scattering_pdf(&self, _ray: &Ray, hit_rec: &HitRecord, scattered: &Ray) -> f64 {
+        let cosine = Vec3::dot(&hit_rec.shading_normal, &scattered.dir.to_unit());
+        if cosine.is_sign_negative() {
+            -cosine * self.trans / PI
+        } else {
+            cosine * (1. - self.trans) / PI
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::INFINITY;
+
+    use crate::{
+        hittable::{object::rectangle::Rectangle, Hittable},
+        material::{diffuse_light::DiffuseLight, lambertian::Lambertian},
+    };
+
+    use super::*;
+
+    fn hit_from(rect: &dyn Hittable, y: f64) -> HitRecord {
+        let ray = Ray::new(Point3::new(1., y, 1.), Vec3::new(0., -y, 0.), 0.);
+        rect.hit(&ray, 0., INFINITY).unwrap()
+    }
+
+    #[test]
+    fn face_dispatch() {
+        let front = RGBColor::new(0.8, 0.1, 0.1);
+        let back = RGBColor::new(0.1, 0.1, 0.8);
+        let mat = TwoSided::new(
+            Lambertian::new_from_color(front),
+            Lambertian::new_from_color(back),
+        );
+        // xz 平面, 外侧法向量为 +y
+        let rect = Rectangle::new(2, 0., 2., 0., 2., 0., mat);
+
+        let rec = hit_from(&rect, 5.);
+        assert!(rec.front_face);
+        let sca = rec.mat.scatter(
+            &Ray::new(Point3::default(), Vec3::new(0., -1., 0.), 0.),
+            &rec,
+        );
+        assert_eq!(sca.unwrap().attenutaion, front);
+
+        let rec = hit_from(&rect, -5.);
+        assert!(!rec.front_face);
+        let sca = rec.mat.scatter(
+            &Ray::new(Point3::default(), Vec3::new(0., 1., 0.), 0.),
+            &rec,
+        );
+        assert_eq!(sca.unwrap().attenutaion, back);
+    }
+
+    #[test]
+    fn emission_dispatch() {
+        let light = RGBColor::new(4., 4., 4.);
+        let mat = TwoSided::new(
+            DiffuseLight::new_from_color(light),
+            Lambertian::new_from_color(RGBColor::new(0.5, 0.5, 0.5)),
+        );
+        let rect = Rectangle::new(2, 0., 2., 0., 2., 0., mat);
+        for &(y, expected) in &[(5., light), (-5., RGBColor::default())] {
+            let ray = Ray::new(Point3::new(1., y, 1.), Vec3::new(0., -y, 0.), 0.);
+            let rec = rect.hit(&ray, 0., INFINITY).unwrap();
+            assert_eq!(rec.mat.emitted(&ray, &rec, rec.u, rec.v, rec.p), expected);
+        }
+    }
+
+    #[test]
+    fn translucent_pdf() {
+        let mat = Translucent::new_from_color(RGBColor::new(0.5, 0.5, 0.5), 0.25);
+        let rect = Rectangle::new(2, 0., 2., 0., 2., 0., mat);
+        let ray = Ray::new(Point3::new(1., 5., 1.), Vec3::new(0., -1., 0.), 0.);
+        let rec = rect.hit(&ray, 0., INFINITY).unwrap();
+
+        // 反射侧占 1 - trans, 透射侧占 trans
+        let up = Ray::new(rec.p, rec.shading_normal, 0.);
+        let down = Ray::new(rec.p, -rec.shading_normal, 0.);
+        assert!((rec.mat.scattering_pdf(&ray, &rec, &up) - 0.75 / PI).abs() < 1e-9);
+        assert!((rec.mat.scattering_pdf(&ray, &rec, &down) - 0.25 / PI).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{background::Background, basic::vec3::Vec3};
+
+use super::PDF;
+
+pub struct BackgroundPDF<'a> {
+    background: &'a dyn Background,
+}
+
+impl<'a> BackgroundPDF<'a> {
+    pub fn new(background: &'a dyn Background) -> Self {
+        Self { background }
+    }
+
+    pub fn sampleable(&self) -> bool {
+        self.background.sampleable()
+    }
+}
+
+impl PDF for BackgroundPDF<'_> {
+    fn value(&self, dir: &Vec3) -> f64 {
+        self.background.pdf_value(dir)
+    }
+
+    fn generate(&self) -> Vec3 {
+        self.background.rand_dir()
+    }
+}
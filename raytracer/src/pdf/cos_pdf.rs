@@ -1,17 +1,26 @@
 use std::f64::consts::PI;
 
-use crate::basic::{onb::ONB, vec3::Vec3};
+use crate::basic::{onb::ONB, rand_1, vec3::Vec3};
 
 use super::PDF;
 
 pub struct CosinePDF {
     pub uvw: ONB,
+    pub trans: f64, // 以 trans 的概率向 w 反侧半球散射, 用于薄表面透射
 }
 
 impl CosinePDF {
     pub fn new(w: Vec3) -> Self {
         Self {
             uvw: ONB::build_from_w(&w),
+            trans: 0.,
+        }
+    }
+
+    pub fn new_two_sided(w: Vec3, trans: f64) -> Self {
+        Self {
+            uvw: ONB::build_from_w(&w),
+            trans,
         }
     }
 }
@@ -19,10 +28,37 @@ impl CosinePDF {
 impl PDF for CosinePDF {
     fn value(&self, dir: &Vec3) -> f64 {
         let cos = Vec3::dot(&dir.to_unit(), &self.uvw.w());
-        cos / PI
+        if cos.is_sign_negative() && self.trans > 0. {
+            -cos * self.trans / PI
+        } else {
+            cos.max(0.) * (1. - self.trans) / PI
+        }
     }
 
     fn generate(&self) -> Vec3 {
-        self.uvw.local(&Vec3::rand_cos_dir())
+        let dir = self.uvw.local(&Vec3::rand_cos_dir());
+        if self.trans > 0. && rand_1() < self.trans {
+            dir - self.uvw.w() * Vec3::dot(&dir, &self.uvw.w()) * 2.
+        } else {
+            dir
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_negative_value() {
+        let w = Vec3::new(0., 1., 0.);
+        let pdf = CosinePDF::new(w);
+        assert!((pdf.value(&w) - 1. / PI).abs() < 1e-9);
+        assert!(pdf.value(&-w) == 0.);
+        assert!(pdf.value(&Vec3::new(1., -0.2, 0.)) == 0.);
+
+        let two_sided = CosinePDF::new_two_sided(w, 0.25);
+        assert!((two_sided.value(&-w) - 0.25 / PI).abs() < 1e-9);
+        assert!((two_sided.value(&w) - 0.75 / PI).abs() < 1e-9);
     }
 }
@@ -1,6 +1,7 @@
 use crate::{
     basic::vec3::{Point3, Vec3},
     hittable::Hittable,
+    light::light_sampler::LightSampler,
 };
 
 use super::PDF;
@@ -28,3 +29,9 @@ impl<TH: Hittable> PDF for HittablePDF<'_, TH> {
         self.obj.rand_dir(&self.orig)
     }
 }
+
+impl HittablePDF<'_, LightSampler> {
+    pub fn sampleable(&self) -> bool {
+        !self.obj.is_empty()
+    }
+}
@@ -1,9 +1,14 @@
+pub mod background_pdf;
 pub mod cos_pdf;
 pub mod hittable_pdf;
+pub mod phase_pdf;
 
-use crate::{basic::vec3::Vec3, hittable::Hittable};
+use crate::{
+    basic::{rand_1, vec3::Vec3},
+    light::light_sampler::LightSampler,
+};
 
-use self::{cos_pdf::CosinePDF, hittable_pdf::HittablePDF};
+use self::{background_pdf::BackgroundPDF, hittable_pdf::HittablePDF};
 
 pub trait PDF {
     fn value(&self, dir: &Vec3) -> f64;
@@ -13,34 +18,99 @@ pub trait PDF {
 
 //=================================================
 
-pub struct MixedPDF<'a, TH>
-where
-    TH: Hittable,
-{
-    scatter_pdf: CosinePDF,
-    _light_pdf: HittablePDF<'a, TH>,
+pub struct MixedPDF<'a> {
+    // 在材质, 光源, 背景三者中可用的部分等概率混合
+    scatter_pdf: Box<dyn PDF>,
+    light_pdf: HittablePDF<'a, LightSampler>,
+    background_pdf: BackgroundPDF<'a>,
 }
 
-impl<'a, TH: Hittable> MixedPDF<'a, TH> {
-    pub fn new(scatter_pdf: CosinePDF, light_pdf: HittablePDF<'a, TH>) -> Self {
+impl<'a> MixedPDF<'a> {
+    pub fn new(
+        scatter_pdf: Box<dyn PDF>,
+        light_pdf: HittablePDF<'a, LightSampler>,
+        background_pdf: BackgroundPDF<'a>,
+    ) -> Self {
         Self {
             scatter_pdf,
-            _light_pdf: light_pdf,
+            light_pdf,
+            background_pdf,
         }
     }
 }
 
-impl<'a, TH: Hittable> PDF for MixedPDF<'a, TH> {
+impl<'a> PDF for MixedPDF<'a> {
     fn value(&self, dir: &Vec3) -> f64 {
-        self.scatter_pdf.value(dir)
-        //  + self.light_pdf.value(dir)
+        let mut sum = self.scatter_pdf.value(dir);
+        let mut cnt = 1.;
+        if self.light_pdf.sampleable() {
+            sum += self.light_pdf.value(dir);
+            cnt += 1.;
+        }
+        if self.background_pdf.sampleable() {
+            sum += self.background_pdf.value(dir);
+            cnt += 1.;
+        }
+        sum / cnt
     }
 
     fn generate(&self) -> Vec3 {
-        // if rand_1() < 0.7 {
-        self.scatter_pdf.generate()
-        // } else {
-        //     self.light_pdf.generate()
-        // }
+        let light = self.light_pdf.sampleable();
+        let background = self.background_pdf.sampleable();
+        let cnt = 1 + light as usize + background as usize;
+        let k = (rand_1() * cnt as f64) as usize;
+
+        if light && k == 1 {
+            self.light_pdf.generate()
+        } else if background && k == cnt - 1 && k > 0 {
+            self.background_pdf.generate()
+        } else {
+            self.scatter_pdf.generate()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::{
+        background::SolidBackground,
+        basic::vec3::{Point3, RGBColor},
+        hittable::{object::ring::Ring, HittableList},
+        material::diffuse_light::DiffuseLight,
+    };
+
+    use super::{phase_pdf::HenyeyGreensteinPDF, *};
+
+    #[test]
+    fn mixed_phase_pdf_normalized() {
+        // 介质内散射点: 相函数与圆环光源混合后仍为归一化的概率密度
+        let orig = Point3::new(0., 1., 0.);
+        let mut list = HittableList::default();
+        list.add(Ring::new(
+            2.,
+            1.,
+            DiffuseLight::new_from_color(RGBColor::new(1., 1., 1.)),
+        ));
+        let lights = LightSampler::new(list);
+        let background = SolidBackground::new(RGBColor::default());
+        let mixed_pdf = MixedPDF::new(
+            Box::new(HenyeyGreensteinPDF::new(Vec3::new(1., 0., 0.), 0.6)),
+            HittablePDF::new(orig, &lights),
+            BackgroundPDF::new(&background),
+        );
+
+        let n = 400000;
+        let mut sum = 0.;
+        for _i in 0..n {
+            // 球面上均匀采样
+            let z = 1. - 2. * rand_1();
+            let phi = 2. * PI * rand_1();
+            let r = (1. - z * z).sqrt();
+            let dir = Vec3::new(r * phi.cos(), r * phi.sin(), z);
+            sum += mixed_pdf.value(&dir) * 4. * PI;
+        }
+        assert!((sum / n as f64 - 1.).abs() < 0.02);
     }
 }
@@ -0,0 +1,49 @@
+use std::f64::consts::PI;
+
+use crate::basic::{clamp_oi, onb::ONB, rand_1, vec3::Vec3};
+
+use super::PDF;
+
+// Henyey-Greenstein 相函数, cos 为散射方向与光线传播方向的夹角余弦
+pub fn henyey_greenstein(cos: f64, g: f64) -> f64 {
+    let denom = 1. + g * g - 2. * g * cos;
+    (1. - g * g) / (4. * PI * denom * denom.sqrt())
+}
+
+pub struct HenyeyGreensteinPDF {
+    pub uvw: ONB, // w 为入射光线的传播方向
+    pub g: f64,   // 不对称参数, > 0 前向散射, < 0 后向散射, 0 为各向同性
+}
+
+impl HenyeyGreensteinPDF {
+    pub fn new(dir: Vec3, g: f64) -> Self {
+        Self {
+            uvw: ONB::build_from_w(&dir),
+            g,
+        }
+    }
+}
+
+impl PDF for HenyeyGreensteinPDF {
+    fn value(&self, dir: &Vec3) -> f64 {
+        let cos = Vec3::dot(&dir.to_unit(), &self.uvw.w());
+        henyey_greenstein(cos, self.g)
+    }
+
+    fn generate(&self) -> Vec3 {
+        let g = self.g;
+        let xi = rand_1();
+        let cos = if g.abs() < 1e-3 {
+            1. - 2. * xi
+        } else {
+            let k = (1. - g * g) / (1. - g + 2. * g * xi);
+            (1. + g * g - k * k) / (2. * g)
+        };
+        let cos = clamp_oi(cos, -1., 1.);
+        let sin = (1. - cos * cos).sqrt();
+        let phi = 2. * PI * rand_1();
+
+        self.uvw
+            .local(&Vec3::new(f64::cos(phi) * sin, f64::sin(phi) * sin, cos))
+    }
+}
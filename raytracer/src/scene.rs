@@ -3,7 +3,10 @@ use std::f64::consts::PI;
 use rand::{prelude::StdRng, Rng, SeedableRng};
 
 use crate::{
-    basic::vec3::{Point3, RGBColor, Vec3},
+    basic::{
+        color::srgb_color_to_linear,
+        vec3::{Point3, RGBColor, Vec3},
+    },
     bvh::bvh_node::BvhNode,
     hittable::{
         instance::{
@@ -26,18 +29,28 @@ use crate::{
         lambertian::Lambertian,
         metal::{ColoredMetal, Metal},
     },
-    texture::{checker::Checker, gradient::Gradient, solid_color::SolidColor},
+    texture::{
+        checker::Checker,
+        gradient::{Gradient, GradientInput},
+        registry::TextureRegistry,
+        solid_color::SolidColor,
+    },
 };
 
-pub fn _cornell_box_bvh(world: &mut HittableList, lights: &mut HittableList) {
+pub fn _cornell_box_bvh(
+    world: &mut HittableList,
+    lights: &mut HittableList,
+    _textures: &TextureRegistry,
+) {
     let mut objects = HittableList::default();
 
     // Material
     let red = Lambertian::new(SolidColor::new_from_value(0.65, 0.05, 0.05));
     let green = Lambertian::new(SolidColor::new_from_value(0.12, 0.45, 0.15));
     let white = Lambertian::new(SolidColor::new_from_value(0.73, 0.73, 0.73));
-    let light_white = DiffuseLight::new_from_color(RGBColor::new(255., 223., 184.) / 255. * 30.); // Color of 4700K
-    let light_gloden = DiffuseLight::new_from_color(RGBColor::new(248., 231., 28.) / 255. * 120.);
+    let light_white = DiffuseLight::new_from_temperature(4700., 30.);
+    let light_gloden =
+        DiffuseLight::new_from_color(RGBColor::new(248., 231., 28.) / 255.).with_strength(120.);
     let aluminum = Metal::new(RGBColor::new(0.8, 0.85, 0.88), 0.);
     let glass = Dielectric::new(1.5);
 
@@ -117,7 +130,11 @@ pub fn _cornell_box_bvh(world: &mut HittableList, lights: &mut HittableList) {
     lights.add(triangle);
 }
 
-pub fn paper_world(world: &mut HittableList, _lights: &mut HittableList) {
+pub fn paper_world(
+    world: &mut HittableList,
+    lights: &mut HittableList,
+    textures: &TextureRegistry,
+) {
     let mut objects = HittableList::default();
 
     // Material
@@ -127,42 +144,26 @@ pub fn paper_world(world: &mut HittableList, _lights: &mut HittableList) {
     // let black_metal = Metal::new(RGBColor::new(0.2, 0.2, 0.2), 0.01);
     // let white_metal = Metal::new(RGBColor::new(0.9, 0.9, 0.9), 0.01);
 
-    // Stars
-    let light_star = [
-        DiffuseLight::new(Gradient::new(
-            vec![
-                RGBColor::new(217., 250., 255.) / 255. * 1., //.pow2() / 65025.
-                RGBColor::new(120., 238., 255.) / 255. * 1.,
-            ],
-            vec![0., 1.],
-        )),
-        DiffuseLight::new(Gradient::new(
-            vec![
-                RGBColor::new(199., 232., 255.) / 255. * 1.,
-                RGBColor::new(84., 225., 255.) / 255. * 1.,
-                RGBColor::new(199., 232., 255.) / 255. * 1.,
-            ],
-            vec![0., 0.3, 1.],
-        )),
-        DiffuseLight::new(Gradient::new(
-            vec![
-                RGBColor::new(255., 251., 182.) / 255. * 1.,
-                RGBColor::new(182., 232., 255.) / 255. * 1.,
-                RGBColor::new(255., 251., 182.) / 255. * 1.,
-                RGBColor::new(182., 232., 255.) / 255. * 1.,
-            ],
-            vec![0., 0.4, 0.8, 1.],
-        )),
-        DiffuseLight::new(Gradient::new(
-            vec![
-                RGBColor::new(238., 236., 211.) / 255. * 1.,
-                RGBColor::new(238., 237., 225.) / 255. * 1.,
-                RGBColor::new(255., 249., 193.) / 255. * 1.,
-                RGBColor::new(238., 236., 211.) / 255. * 1.,
-            ],
-            vec![0., 0.2, 0.7, 1.],
-        )),
+    // Stars, 各圆环共用同一组纹理
+    // 圆环的 v 曾恒为 0, 渐变纹理只显示首个颜色; v 改为径向位置后直接使用该颜色以保持原有效果
+    let star_color = [
+        RGBColor::new(217., 250., 255.) / 255.,
+        RGBColor::new(199., 232., 255.) / 255.,
+        RGBColor::new(255., 251., 182.) / 255.,
+        RGBColor::new(238., 236., 211.) / 255.,
     ];
+    let light_star: Vec<_> = star_color
+        .iter()
+        .enumerate()
+        .map(|(i, col)| {
+            let name = format!("star_{}", i);
+            DiffuseLight::new(
+                textures
+                    .get_or_insert_with(&name, || SolidColor::new(*col))
+                    .unwrap(),
+            )
+        })
+        .collect();
 
     let mut rnd = StdRng::seed_from_u64(19260817);
     let ring_num = 120;
@@ -189,6 +190,7 @@ pub fn paper_world(world: &mut HittableList, _lights: &mut HittableList) {
             1,
             10.,
         );
+        lights.add(moved_ring.clone());
         objects.add(moved_ring);
     }
 
@@ -211,7 +213,8 @@ pub fn paper_world(world: &mut HittableList, _lights: &mut HittableList) {
     objects.add(moved_chess_set);
 
     // Polyhedron
-    let light_polyhedron = DiffuseLight::new_from_color(RGBColor::new(0., 240., 207.) / 255. * 15.);
+    let light_polyhedron =
+        DiffuseLight::new_from_color(RGBColor::new(0., 240., 207.) / 255.).with_strength(15.);
     let polyhedron_light = OBJModel::load_from_file(
         "raytracer/model/Polyhedron.obj",
         0,
@@ -257,15 +260,17 @@ pub fn paper_world(world: &mut HittableList, _lights: &mut HittableList) {
         RGBColor::new(10., 26., 57.),
     ];
     for col in &mut color_set {
-        col.x = col.x.powi(2) / 65025.;
-        col.y = col.y.powi(2) / 65025.;
-        col.z = col.z.powi(2) / 65025.;
+        *col = srgb_color_to_linear(*col / 255.);
     }
     let pos_set = vec![0., 0.50, 0.53, 0.57, 0.62, 1.];
     let background_sphere = Sphere::new(
         Vec3::new(0., -12000., 0.),
         50000.,
-        DiffuseLight::new(Gradient::new(color_set, pos_set)),
+        DiffuseLight::new(
+            Gradient::new(color_set, pos_set)
+                .and_then(|g| g.with_input(GradientInput::Polar))
+                .unwrap(),
+        ),
         // Gradient::new(color_set, pos_set)
     );
     let rotated_background_sphere = Rotate::new(background_sphere, 2, 18.);
@@ -276,5 +281,5 @@ pub fn paper_world(world: &mut HittableList, _lights: &mut HittableList) {
     world.add(BvhNode::new_from_list(objects, 0., 1.));
 
     // Hittable PDF
-    // lights.add(BvhNode::new_from_l);
+    // 圆环已在上方加入 lights; 多面体光源 (OBJModel) 不支持按方向采样, 背景球包围整个场景, 均不加入
 }
@@ -0,0 +1,37 @@
+use crate::basic::{
+    color::blackbody,
+    vec3::{Point3, RGBColor, Vec3},
+};
+
+use super::Texture;
+
+#[derive(Clone)]
+pub struct Blackbody<TT>
+where
+    TT: Texture,
+{
+    // 以纹理亮度 * scale 为色温的黑体辐射颜色, 用于火焰等自发光介质
+    pub temperature: TT,
+    pub scale: f64,     // 纹理亮度为 1 时对应的色温
+    pub intensity: f64, // 亮度缩放
+}
+
+impl<TT: Texture> Blackbody<TT> {
+    pub fn new(temperature: TT, scale: f64, intensity: f64) -> Self {
+        Self {
+            temperature,
+            scale,
+            intensity,
+        }
+    }
+}
+
+impl<TT: Texture> Texture for Blackbody<TT> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        let kelvin = self.temperature.value(u, v, p, normal, tm).luminance() * self.scale;
+        if kelvin <= 0. {
+            return RGBColor::default();
+        }
+        blackbody(kelvin) * self.intensity
+    }
+}
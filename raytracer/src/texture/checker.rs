@@ -1,4 +1,4 @@
-use crate::basic::vec3::{Point3, RGBColor};
+use crate::basic::vec3::{Point3, RGBColor, Vec3};
 
 use super::Texture;
 
@@ -20,22 +20,17 @@ impl<TT1: Texture, TT2: Texture> Checker<TT1, TT2> {
 }
 
 impl<TT1: Texture, TT2: Texture> Texture for Checker<TT1, TT2> {
-    fn value(&self, u: f64, v: f64, p: Point3) -> RGBColor {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
         // shader networks introduced by Pat Hanrahan
         let sines =
             f64::sin(self.scale * p.x) * f64::sin(self.scale * p.y) * f64::sin(self.scale * p.z);
         // println!("{} {}", sines, p);
 
-        // 棒棒糖
-        // let odevity = (u * 16. + v * 16.) as i32;
-        // 简单网格
-        // let odevity = (u * 16.) as i32 + (v * 16.) as i32;
-
+        // UV 空间的棋盘格等图案见 uv_pattern
         if sines > 0. {
-            // if odevity % 2 == 0 {
-            self.even.value(u, v, p)
+            self.even.value(u, v, p, normal, tm)
         } else {
-            self.odd.value(u, v, p)
+            self.odd.value(u, v, p, normal, tm)
         }
     }
 }
@@ -0,0 +1,248 @@
+use crate::basic::{
+    clamp_oi,
+    color::{hsv_to_rgb, rgb_to_hsv},
+    vec3::{Point3, RGBColor, Vec3},
+};
+
+use super::Texture;
+
+// 纹理运算节点, 可相互嵌套组合出类似 shader network 的纹理
+
+#[derive(Clone)]
+pub struct TextureAdd<TT1, TT2>
+where
+    TT1: Texture,
+    TT2: Texture,
+{
+    pub tex1: TT1,
+    pub tex2: TT2,
+}
+
+impl<TT1: Texture, TT2: Texture> TextureAdd<TT1, TT2> {
+    pub fn new(tex1: TT1, tex2: TT2) -> Self {
+        Self { tex1, tex2 }
+    }
+}
+
+impl<TT1: Texture, TT2: Texture> Texture for TextureAdd<TT1, TT2> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        self.tex1.value(u, v, p, normal, tm) + self.tex2.value(u, v, p, normal, tm)
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct TextureMultiply<TT1, TT2>
+where
+    TT1: Texture,
+    TT2: Texture,
+{
+    pub tex1: TT1,
+    pub tex2: TT2,
+}
+
+impl<TT1: Texture, TT2: Texture> TextureMultiply<TT1, TT2> {
+    pub fn new(tex1: TT1, tex2: TT2) -> Self {
+        Self { tex1, tex2 }
+    }
+}
+
+impl<TT1: Texture, TT2: Texture> Texture for TextureMultiply<TT1, TT2> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        self.tex1.value(u, v, p, normal, tm) * self.tex2.value(u, v, p, normal, tm)
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct TextureLerp<TT1, TT2, TM>
+where
+    TT1: Texture,
+    TT2: Texture,
+    TM: Texture,
+{
+    // 以 mask 各通道为权重在两纹理间插值, mask 为 0 时取 tex1, 为 1 时取 tex2
+    pub tex1: TT1,
+    pub tex2: TT2,
+    pub mask: TM,
+}
+
+impl<TT1: Texture, TT2: Texture, TM: Texture> TextureLerp<TT1, TT2, TM> {
+    pub fn new(tex1: TT1, tex2: TT2, mask: TM) -> Self {
+        Self { tex1, tex2, mask }
+    }
+}
+
+impl<TT1: Texture, TT2: Texture, TM: Texture> Texture for TextureLerp<TT1, TT2, TM> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        let k = self.mask.value(u, v, p, normal, tm);
+        self.tex1.value(u, v, p, normal, tm) * (RGBColor::new(1., 1., 1.) - k)
+            + self.tex2.value(u, v, p, normal, tm) * k
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct Invert<TT>
+where
+    TT: Texture,
+{
+    // 1 - color
+    pub texture: TT,
+}
+
+impl<TT: Texture> Invert<TT> {
+    pub fn new(texture: TT) -> Self {
+        Self { texture }
+    }
+}
+
+impl<TT: Texture> Texture for Invert<TT> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        RGBColor::new(1., 1., 1.) - self.texture.value(u, v, p, normal, tm)
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct Clamp<TT>
+where
+    TT: Texture,
+{
+    pub texture: TT,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl<TT: Texture> Clamp<TT> {
+    pub fn new(texture: TT, min: f64, max: f64) -> Self {
+        Self { texture, min, max }
+    }
+}
+
+impl<TT: Texture> Texture for Clamp<TT> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        let col = self.texture.value(u, v, p, normal, tm);
+        RGBColor::new(
+            clamp_oi(col.x, self.min, self.max),
+            clamp_oi(col.y, self.min, self.max),
+            clamp_oi(col.z, self.min, self.max),
+        )
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct HSVAdjust<TT>
+where
+    TT: Texture,
+{
+    pub texture: TT,
+    pub hue_shift: f64,  // 单位为度
+    pub saturation: f64, // 饱和度缩放
+    pub brightness: f64, // 明度缩放
+}
+
+impl<TT: Texture> HSVAdjust<TT> {
+    pub fn new(texture: TT, hue_shift: f64, saturation: f64, brightness: f64) -> Self {
+        Self {
+            texture,
+            hue_shift,
+            saturation,
+            brightness,
+        }
+    }
+}
+
+impl<TT: Texture> Texture for HSVAdjust<TT> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        let (h, s, val) = rgb_to_hsv(self.texture.value(u, v, p, normal, tm));
+        hsv_to_rgb(
+            h + self.hue_shift,
+            clamp_oi(s * self.saturation, 0., 1.),
+            (val * self.brightness).max(0.),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::texture::solid_color::SolidColor;
+
+    use super::*;
+
+    fn value<TT: Texture>(tex: &TT) -> RGBColor {
+        tex.value(0.3, 0.6, Point3::default(), Vec3::default(), 0.)
+    }
+
+    fn near(a: RGBColor, b: RGBColor) -> bool {
+        (a - b).length() < 1e-9
+    }
+
+    #[test]
+    fn arithmetic_nodes() {
+        let a = || SolidColor::new_from_value(0.2, 0.4, 0.6);
+        let b = || SolidColor::new_from_value(0.5, 0.5, 2.);
+
+        assert!(near(
+            value(&TextureAdd::new(a(), b())),
+            RGBColor::new(0.7, 0.9, 2.6)
+        ));
+        assert!(near(
+            value(&TextureMultiply::new(a(), b())),
+            RGBColor::new(0.1, 0.2, 1.2)
+        ));
+
+        // mask 逐通道插值: 0 取 tex1, 1 取 tex2
+        let mask = SolidColor::new_from_value(0., 1., 0.5);
+        let lerp = TextureLerp::new(a(), b(), mask);
+        assert!(near(value(&lerp), RGBColor::new(0.2, 0.5, 1.3)));
+
+        // 可相互嵌套
+        let nested = TextureAdd::new(TextureMultiply::new(a(), b()), Invert::new(a()));
+        assert!(near(value(&nested), RGBColor::new(0.9, 0.8, 1.6)));
+    }
+
+    #[test]
+    fn adjustment_nodes() {
+        let col = SolidColor::new_from_value(-0.5, 0.25, 1.5);
+        assert!(near(
+            value(&Invert::new(col.clone())),
+            RGBColor::new(1.5, 0.75, -0.5)
+        ));
+        assert!(near(
+            value(&Clamp::new(col, 0., 1.)),
+            RGBColor::new(0., 0.25, 1.)
+        ));
+
+        let red = || SolidColor::new_from_value(1., 0., 0.);
+        // 色相旋转 120° 由红变绿, 饱和度为 0 时变为灰色
+        assert!(near(
+            value(&HSVAdjust::new(red(), 120., 1., 1.)),
+            RGBColor::new(0., 1., 0.)
+        ));
+        assert!(near(
+            value(&HSVAdjust::new(red(), -120., 1., 1.)),
+            RGBColor::new(0., 0., 1.)
+        ));
+        assert!(near(
+            value(&HSVAdjust::new(red(), 0., 0., 0.5)),
+            RGBColor::new(0.5, 0.5, 0.5)
+        ));
+        assert!(near(
+            value(&HSVAdjust::new(red(), 0., 0.5, 1.)),
+            RGBColor::new(1., 0.5, 0.5)
+        ));
+        // 饱和度缩放后不超过 1
+        let pink = SolidColor::new_from_value(1., 0.5, 0.5);
+        assert!(near(
+            value(&HSVAdjust::new(pink, 0., 4., 1.)),
+            RGBColor::new(1., 0., 0.)
+        ));
+    }
+}
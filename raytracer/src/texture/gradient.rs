@@ -1,58 +1,156 @@
 use std::f64::consts::PI;
 
-use crate::basic::vec3::{Point3, RGBColor};
+use crate::basic::{
+    clamp_oi, f64_equal,
+    vec3::{Point3, RGBColor, Vec3},
+    INFINITESIMAL,
+};
 
-use super::Texture;
+use super::{solid_color::SolidColor, Texture};
 
 #[derive(Clone)]
-pub struct Gradient {
-    pub color_set: Vec<RGBColor>,
-    pub pos_set: Vec<f64>,
+pub enum GradientInput<TT = SolidColor>
+where
+    TT: Texture,
+{
+    // 渐变的自变量, 最终映射到颜色序列的位置上
+    // Axis 与 Radial 直接使用世界坐标中的碰撞点, 需随物体移动时,
+    // 以 PointTransform 包裹整个 Gradient 将碰撞点变换至物体空间
+    U,
+    V,
+    Polar, // 球体球坐标中的纬度: (1 - cos(v * PI)) / 2, 两极附近变化平缓
+    Axis {
+        dir: Vec3, // 沿该方向投影, 可为任意世界坐标方向
+        min: f64,  // 投影值为 min 时对应位置 0
+        max: f64,  // 投影值为 max 时对应位置 1
+    },
+    Radial {
+        center: Point3,
+        radius: f64, // 距离为 radius 时对应位置 1
+    },
+    Luminance(TT), // 以另一纹理的亮度为自变量
+    Time,          // 以光线时刻为自变量, 此时各颜色即为关键帧
+}
+
+#[derive(Clone, Copy)]
+pub enum GradientInterpolation {
+    Linear,
+    Constant, // 取区间左端点颜色
+    Smoothstep,
+    Cosine,
+}
 
-    color_sub: Vec<RGBColor>,
-    pos_sub: Vec<f64>,
-    last_n: usize,
+#[derive(Clone)]
+pub struct Gradient<TT = SolidColor>
+where
+    TT: Texture,
+{
+    pub input: GradientInput<TT>,
+    pub interpolation: GradientInterpolation,
+    color_set: Vec<RGBColor>,
+    pos_set: Vec<f64>, // 严格递增
 }
 
 impl Gradient {
-    pub fn new(color_set: Vec<RGBColor>, pos_set: Vec<f64>) -> Self {
-        if pos_set.len() < 2 || color_set.len() != pos_set.len() {
-            panic!("Get wrong input in texture::Gradient");
+    pub fn new(color_set: Vec<RGBColor>, pos_set: Vec<f64>) -> Result<Self, String> {
+        if pos_set.len() < 2 {
+            return Err("Gradient needs at least 2 color stops".to_string());
         }
-
-        let last_n = pos_set.len() - 1;
-        let mut color_sub = Vec::<RGBColor>::new();
-        for i in 0..last_n {
-            color_sub.push(color_set[i + 1] - color_set[i]);
+        if color_set.len() != pos_set.len() {
+            return Err(format!(
+                "Gradient gets {} colors but {} positions",
+                color_set.len(),
+                pos_set.len()
+            ));
         }
-        let mut pos_sub = Vec::<f64>::new();
-        for i in 0..last_n {
-            pos_sub.push(pos_set[i + 1] - pos_set[i]);
+        if pos_set.windows(2).any(|w| w[0] >= w[1]) {
+            return Err("Gradient positions must be strictly increasing".to_string());
         }
 
-        Self {
+        Ok(Self {
+            input: GradientInput::V,
+            interpolation: GradientInterpolation::Linear,
             color_set,
             pos_set,
-            color_sub,
-            pos_sub,
-            last_n,
-        }
+        })
     }
 }
 
-impl Texture for Gradient {
-    fn value(&self, _u: f64, v: f64, _p: Point3) -> RGBColor {
-        // 正常来说如果 u,v 在 xy 平面上, 那么渐变通常随 x 变化。
-        // 但是考虑到本程序中渐变用于球体球坐标, 故以 v 为自变量
-        let mut col_id = 1;
-        let k = (-f64::cos(v * PI) + 1.) / 2.;
-        while col_id < self.last_n && k > self.pos_set[col_id] {
-            col_id += 1;
+impl<TT: Texture> Gradient<TT> {
+    pub fn with_input(mut self, input: GradientInput<TT>) -> Result<Self, String> {
+        match &input {
+            GradientInput::Axis { dir, min, max } => {
+                if dir.is_zero() {
+                    return Err("Gradient axis direction must be non-zero".to_string());
+                }
+                if f64_equal(*min, *max) {
+                    return Err(format!("Gradient axis range [{}, {}] is empty", min, max));
+                }
+            }
+            GradientInput::Radial { radius, .. } if *radius < INFINITESIMAL => {
+                return Err(format!("Gradient gets non-positive radius {}", radius));
+            }
+            _ => (),
         }
-        col_id -= 1;
 
-        self.color_set[col_id]
-            + self.color_sub[col_id] * ((k - self.pos_set[col_id]) / self.pos_sub[col_id])
+        self.input = input;
+        Ok(self)
+    }
+
+    // 以另一纹理的亮度为自变量
+    pub fn with_luminance_input<TT2: Texture>(self, texture: TT2) -> Gradient<TT2> {
+        Gradient {
+            input: GradientInput::Luminance(texture),
+            interpolation: self.interpolation,
+            color_set: self.color_set,
+            pos_set: self.pos_set,
+        }
+    }
+
+    pub fn with_interpolation(mut self, interpolation: GradientInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn color_set(&self) -> &[RGBColor] {
+        &self.color_set
+    }
+
+    pub fn pos_set(&self) -> &[f64] {
+        &self.pos_set
+    }
+
+    // 位置 k 处的颜色, 也可作为其他纹理的颜色映射 (color ramp), 超出范围时取两端颜色
+    pub fn color_at(&self, k: f64) -> RGBColor {
+        let n = self.pos_set.len();
+        let k = clamp_oi(k, self.pos_set[0], self.pos_set[n - 1]);
+        let id = self.pos_set.partition_point(|p| *p <= k).clamp(1, n - 1) - 1;
+
+        let t = (k - self.pos_set[id]) / (self.pos_set[id + 1] - self.pos_set[id]);
+        let t = match self.interpolation {
+            GradientInterpolation::Linear => t,
+            GradientInterpolation::Constant => 0.,
+            GradientInterpolation::Smoothstep => t * t * (3. - 2. * t),
+            GradientInterpolation::Cosine => (1. - f64::cos(t * PI)) / 2.,
+        };
+        self.color_set[id] + (self.color_set[id + 1] - self.color_set[id]) * t
+    }
+}
+
+impl<TT: Texture> Texture for Gradient<TT> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        let k = match &self.input {
+            GradientInput::U => u,
+            GradientInput::V => v,
+            GradientInput::Polar => (1. - f64::cos(v * PI)) / 2.,
+            GradientInput::Axis { dir, min, max } => {
+                (Vec3::dot(&p, &dir.to_unit()) - min) / (max - min)
+            }
+            GradientInput::Radial { center, radius } => (p - *center).length() / radius,
+            GradientInput::Luminance(texture) => texture.value(u, v, p, normal, tm).luminance(),
+            GradientInput::Time => tm,
+        };
+        self.color_at(k)
     }
 }
 
@@ -71,9 +169,102 @@ mod tests {
                 RGBColor::new(0., 0., 1.),
             ],
             vec![0., 0.5, 1.],
-        );
+        )
+        .unwrap()
+        .with_input(GradientInput::Polar)
+        .unwrap();
         for i in 0..11 {
-            println!("{}", g.value(0., 0.1 * i as f64, Vec3::default()));
+            println!(
+                "{}",
+                g.value(0., 0.1 * i as f64, Vec3::default(), Vec3::default(), 0.)
+            );
         }
     }
+
+    #[test]
+    fn test_input_and_interpolation() {
+        let black = RGBColor::new(0., 0., 0.);
+        let white = RGBColor::new(1., 1., 1.);
+        assert!(Gradient::new(vec![black], vec![0.]).is_err());
+        assert!(Gradient::new(vec![black, white], vec![0.]).is_err());
+        assert!(Gradient::new(vec![black, white], vec![1., 0.]).is_err());
+
+        let g = Gradient::new(vec![black, white], vec![0., 1.]).unwrap();
+        assert!((g.value(0., 0.25, Vec3::default(), Vec3::default(), 0.).x - 0.25).abs() < 1e-9);
+        assert!((g.color_at(2.).x - 1.).abs() < 1e-9);
+        let constant = g
+            .clone()
+            .with_interpolation(GradientInterpolation::Constant);
+        assert!(constant.color_at(0.9).x.abs() < 1e-9);
+        let smooth = g
+            .clone()
+            .with_interpolation(GradientInterpolation::Smoothstep);
+        assert!((smooth.color_at(0.5).x - 0.5).abs() < 1e-9);
+
+        let axis = g
+            .clone()
+            .with_input(GradientInput::Axis {
+                dir: Vec3::new(0., 2., 0.),
+                min: 10.,
+                max: 20.,
+            })
+            .unwrap();
+        assert!(
+            (axis
+                .value(0., 0., Vec3::new(5., 12.5, 0.), Vec3::default(), 0.)
+                .x
+                - 0.25)
+                .abs()
+                < 1e-9
+        );
+        let radial = g
+            .clone()
+            .with_input(GradientInput::Radial {
+                center: Vec3::new(1., 1., 1.),
+                radius: 4.,
+            })
+            .unwrap();
+        assert!(
+            (radial
+                .value(0., 0., Vec3::new(1., 3., 1.), Vec3::default(), 0.)
+                .x
+                - 0.5)
+                .abs()
+                < 1e-9
+        );
+        let keyframe = g.clone().with_input(GradientInput::Time).unwrap();
+        assert!(g
+            .clone()
+            .with_input(GradientInput::Axis {
+                dir: Vec3::new(1., 0., 0.),
+                min: 3.,
+                max: 3.,
+            })
+            .is_err());
+        assert!(g
+            .clone()
+            .with_input(GradientInput::Axis {
+                dir: Vec3::default(),
+                min: 0.,
+                max: 1.,
+            })
+            .is_err());
+        assert!(g
+            .clone()
+            .with_input(GradientInput::Radial {
+                center: Vec3::default(),
+                radius: 0.,
+            })
+            .is_err());
+        assert!(
+            (keyframe
+                .value(0., 0., Vec3::default(), Vec3::default(), 0.4)
+                .x
+                - 0.4)
+                .abs()
+                < 1e-9
+        );
+        let lum = g.with_luminance_input(SolidColor::new(white * 0.75));
+        assert!((lum.value(0., 0., Vec3::default(), Vec3::default(), 0.).x - 0.75).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,108 @@
+use crate::basic::{
+    color::ColorSpace,
+    vec3::{Point3, RGBColor, Vec3},
+};
+
+use super::{image_texture::ImageTexture, registry::TextureRegistry, Texture};
+
+#[derive(Clone)]
+pub struct ImageSequence {
+    // 按光线时刻逐帧切换的图片序列, 用于屏幕等动态自发光
+    pub frames: Vec<ImageTexture>,
+    pub fps: f64,        // 单位时间内的帧数, 时间单位与 Ray::tm 相同
+    pub start_time: f64, // 第 0 帧开始的时刻
+    pub looping: bool,   // 为假时超出范围取首帧或末帧
+}
+
+impl ImageSequence {
+    pub fn new(frames: Vec<ImageTexture>, fps: f64) -> Result<Self, String> {
+        if frames.is_empty() {
+            return Err("ImageSequence needs at least 1 frame".to_string());
+        }
+        if fps <= 0. {
+            return Err(format!("ImageSequence gets non-positive fps {}", fps));
+        }
+
+        Ok(Self {
+            frames,
+            fps,
+            start_time: 0.,
+            looping: true,
+        })
+    }
+
+    // 经由 registry 读取各帧, 同一文件只加载一次
+    pub fn load_from_files(
+        textures: &TextureRegistry,
+        file_names: &[&str],
+        color_space: ColorSpace,
+        fps: f64,
+    ) -> Result<Self, String> {
+        let frames = file_names
+            .iter()
+            .map(|file_name| textures.image(file_name, color_space))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(frames, fps)
+    }
+
+    pub fn with_start_time(mut self, start_time: f64) -> Self {
+        self.start_time = start_time;
+        self
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn frame_at(&self, tm: f64) -> usize {
+        let n = self.frames.len() as i64;
+        let id = ((tm - self.start_time) * self.fps).floor() as i64;
+        if self.looping {
+            id.rem_euclid(n) as usize
+        } else {
+            id.clamp(0, n - 1) as usize
+        }
+    }
+}
+
+impl Texture for ImageSequence {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        self.frames[self.frame_at(tm)].value(u, v, p, normal, tm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid_frame(k: f64) -> ImageTexture {
+        ImageTexture::new_from_data(1, 1, vec![RGBColor::new(k, k, k)], ColorSpace::Linear).unwrap()
+    }
+
+    #[test]
+    fn frame_selection() {
+        let frames = (0..4).map(|i| solid_frame(i as f64)).collect();
+        let seq = ImageSequence::new(frames, 2.).unwrap().with_start_time(1.);
+
+        // 每帧持续 0.5, 自 tm = 1 开始
+        assert_eq!(seq.frame_at(1.), 0);
+        assert_eq!(seq.frame_at(1.49), 0);
+        assert_eq!(seq.frame_at(1.5), 1);
+        assert_eq!(seq.frame_at(2.9), 3);
+        // 循环播放时前后均按帧数取模
+        assert_eq!(seq.frame_at(3.), 0);
+        assert_eq!(seq.frame_at(3.6), 1);
+        assert_eq!(seq.frame_at(0.9), 3);
+        let col = seq.value(0.5, 0.5, Point3::default(), Vec3::default(), 2.2);
+        assert!((col.x - 2.).abs() < 1e-9);
+
+        // 不循环时取首帧或末帧
+        let once = seq.with_looping(false);
+        assert_eq!(once.frame_at(0.), 0);
+        assert_eq!(once.frame_at(100.), 3);
+
+        assert!(ImageSequence::new(Vec::new(), 24.).is_err());
+        assert!(ImageSequence::new(vec![solid_frame(0.)], 0.).is_err());
+    }
+}
@@ -1,46 +1,324 @@
-use image::RgbImage;
+use std::{fs::File, io::BufReader, path::Path, sync::Arc};
+
+use image::{codecs::hdr::HdrDecoder, RgbImage};
 
 use crate::basic::{
-    clamp_hoi,
-    vec3::{Point3, RGBColor},
+    color::ColorSpace,
+    vec3::{Point3, RGBColor, Vec3},
 };
 
 use super::Texture;
 
+#[derive(Clone, Copy)]
+pub enum WrapMode {
+    Repeat, // 平铺
+    Mirror, // 镜像平铺
+    Clamp,  // 超出部分取边缘像素
+}
+
+impl WrapMode {
+    // 将可能越界的像素下标映射回 [0, n)
+    fn wrap(&self, i: i64, n: usize) -> usize {
+        let n = n as i64;
+        let ret = match self {
+            WrapMode::Repeat => i.rem_euclid(n),
+            WrapMode::Mirror => {
+                let k = i.rem_euclid(2 * n);
+                if k < n {
+                    k
+                } else {
+                    2 * n - 1 - k
+                }
+            }
+            WrapMode::Clamp => i.clamp(0, n - 1),
+        };
+        ret as usize
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum FilterMode {
+    // Texture::value 不含像素足迹信息, 无法选取 mipmap 级别, 故暂不支持三线性过滤
+    Nearest,
+    Bilinear,
+}
+
+#[derive(Clone)]
+struct ImageData {
+    width: usize,
+    height: usize,
+    data: Vec<RGBColor>, // 按行存储, 第 0 行为图片顶部
+}
+
+impl ImageData {
+    fn texel(&self, i: usize, j: usize) -> RGBColor {
+        self.data[j * self.width + i]
+    }
+}
+
+//=================================================
+
 #[derive(Clone)]
 pub struct ImageTexture {
-    pub image: RgbImage, // ImageBuffer<Rgb<u8>, Vec<u8>>
+    image: Arc<ImageData>,       // 已解码到线性空间; clone 时共享像素数据
+    pub color_space: ColorSpace, // 图片文件中数据所处的颜色空间
+    pub filter: FilterMode,
+    pub wrap: WrapMode,
+    pub uv_scale: (f64, f64),
+    pub uv_offset: (f64, f64),
 }
 
 impl ImageTexture {
-    pub fn new_from_file(file_name: &str) -> Self {
-        let tmp_image;
-        match image::open(file_name) {
-            Ok(ret) => tmp_image = ret,
-            Err(_) => panic!("Opening image fails! File name: \"{}\"", file_name),
+    // .hdr 文件以浮点数读入且始终视为线性数据, 其余格式保留原有位深读入后按 color_space 解码
+    pub fn new_from_file(file_name: &str, color_space: ColorSpace) -> Result<Self, String> {
+        let err = |e: String| format!("Opening image fails! File name: \"{}\" ({})", file_name, e);
+        let is_hdr = Path::new(file_name)
+            .extension()
+            .map_or(false, |ext| ext.eq_ignore_ascii_case("hdr"));
+
+        if is_hdr {
+            let file = File::open(file_name).map_err(|e| err(e.to_string()))?;
+            let decoder = HdrDecoder::new(BufReader::new(file)).map_err(|e| err(e.to_string()))?;
+            let meta = decoder.metadata();
+            let data = decoder
+                .read_image_hdr()
+                .map_err(|e| err(e.to_string()))?
+                .iter()
+                .map(|pixel| RGBColor::new(pixel.0[0] as f64, pixel.0[1] as f64, pixel.0[2] as f64))
+                .collect();
+            Self::new_from_data(
+                meta.width as usize,
+                meta.height as usize,
+                data,
+                ColorSpace::Linear,
+            )
+        } else {
+            let image = image::open(file_name).map_err(|e| err(e.to_string()))?;
+            let color_type = image.color();
+            if color_type.bits_per_pixel() <= 8 * color_type.channel_count() as u16 {
+                return Self::new_from_image(&image.to_rgb8(), color_space);
+            }
+
+            // 16 位图片保留全部精度
+            let image = image.to_rgb16();
+            let color_scale = 1.0 / 65535.0;
+            let data = image
+                .pixels()
+                .map(|pixel| {
+                    RGBColor::new(
+                        pixel.0[0] as f64 * color_scale,
+                        pixel.0[1] as f64 * color_scale,
+                        pixel.0[2] as f64 * color_scale,
+                    )
+                })
+                .collect();
+            Self::new_from_data(
+                image.width() as usize,
+                image.height() as usize,
+                data,
+                color_space,
+            )
         }
+    }
+
+    pub fn new_from_image(image: &RgbImage, color_space: ColorSpace) -> Result<Self, String> {
+        let color_scale = 1.0 / 255.0;
+        let data = image
+            .pixels()
+            .map(|pixel| {
+                RGBColor::new(
+                    pixel.0[0] as f64 * color_scale,
+                    pixel.0[1] as f64 * color_scale,
+                    pixel.0[2] as f64 * color_scale,
+                )
+            })
+            .collect();
+        Self::new_from_data(
+            image.width() as usize,
+            image.height() as usize,
+            data,
+            color_space,
+        )
+    }
 
-        ImageTexture {
-            image: tmp_image.to_rgb8(),
+    // data 按行存储, 第 0 行为图片顶部
+    pub fn new_from_data(
+        width: usize,
+        height: usize,
+        data: Vec<RGBColor>,
+        color_space: ColorSpace,
+    ) -> Result<Self, String> {
+        if width == 0 || height == 0 || data.len() != width * height {
+            return Err(format!(
+                "Image data size mismatch: {}x{} with {} pixels",
+                width,
+                height,
+                data.len()
+            ));
         }
+
+        let data = data
+            .into_iter()
+            .map(|col| color_space.decode(col))
+            .collect();
+
+        Ok(Self {
+            image: Arc::new(ImageData {
+                width,
+                height,
+                data,
+            }),
+            color_space,
+            filter: FilterMode::Bilinear,
+            wrap: WrapMode::Repeat,
+            uv_scale: (1., 1.),
+            uv_offset: (0., 0.),
+        })
+    }
+
+    pub fn with_filter(mut self, filter: FilterMode) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: WrapMode) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    // 纹理坐标变换: (u, v) -> (u * scale + offset)
+    pub fn with_uv_transform(mut self, scale: (f64, f64), offset: (f64, f64)) -> Self {
+        self.uv_scale = scale;
+        self.uv_offset = offset;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.image.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.image.height
+    }
+
+    // 已解码到线性空间的原图像素, 按行存储, 第 0 行为图片顶部
+    pub fn pixels(&self) -> &[RGBColor] {
+        &self.image.data
+    }
+
+    // 两者是否共享同一份像素数据
+    pub fn shares_pixels(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.image, &other.image)
+    }
+
+    fn nearest(&self, u: f64, v: f64) -> RGBColor {
+        let img = &*self.image;
+        let i = self
+            .wrap
+            .wrap((u * img.width as f64).floor() as i64, img.width);
+        let j = self
+            .wrap
+            .wrap(((1. - v) * img.height as f64).floor() as i64, img.height);
+        img.texel(i, j)
+    }
+
+    fn bilinear(&self, u: f64, v: f64) -> RGBColor {
+        let img = &*self.image;
+        // 像素中心位于半整数坐标处
+        let x = u * img.width as f64 - 0.5;
+        let y = (1. - v) * img.height as f64 - 0.5;
+        let (xf, yf) = (x.floor(), y.floor());
+        let (dx, dy) = (x - xf, y - yf);
+        let (i0, j0) = (
+            self.wrap.wrap(xf as i64, img.width),
+            self.wrap.wrap(yf as i64, img.height),
+        );
+        let (i1, j1) = (
+            self.wrap.wrap(xf as i64 + 1, img.width),
+            self.wrap.wrap(yf as i64 + 1, img.height),
+        );
+
+        (img.texel(i0, j0) * (1. - dx) + img.texel(i1, j0) * dx) * (1. - dy)
+            + (img.texel(i0, j1) * (1. - dx) + img.texel(i1, j1) * dx) * dy
+    }
+
+    fn transform_uv(&self, u: f64, v: f64) -> (f64, f64) {
+        (
+            u * self.uv_scale.0 + self.uv_offset.0,
+            v * self.uv_scale.1 + self.uv_offset.1,
+        )
     }
 }
 
 impl Texture for ImageTexture {
-    fn value(&self, mut u: f64, mut v: f64, _p: Point3) -> RGBColor {
-        u = clamp_hoi(u, 0., 1.);
-        v = clamp_hoi(1. - v, 0., 1.);
-        let i = (self.image.width() as f64 * u) as u32;
-        let j = (self.image.height() as f64 * v) as u32;
-        if i >= self.image.width() || j >= self.image.height() {
-            panic!("Unexpected Error in ImageTexture::value");
+    fn value(&self, u: f64, v: f64, _p: Point3, _normal: Vec3, _tm: f64) -> RGBColor {
+        match self.filter {
+            FilterMode::Nearest => {
+                let (u, v) = self.transform_uv(u, v);
+                self.nearest(u, v)
+            }
+            FilterMode::Bilinear => {
+                let (u, v) = self.transform_uv(u, v);
+                self.bilinear(u, v)
+            }
         }
+    }
+}
 
-        let color_scale = 1.0 / 255.0;
-        RGBColor::new(
-            self.image.get_pixel(i, j).0[0] as f64 * color_scale,
-            self.image.get_pixel(i, j).0[1] as f64 * color_scale,
-            self.image.get_pixel(i, j).0[2] as f64 * color_scale,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_modes() {
+        let cases = [
+            (
+                WrapMode::Repeat,
+                [(-1, 3), (-4, 0), (-5, 3), (4, 0), (9, 1)],
+            ),
+            (
+                WrapMode::Mirror,
+                [(-1, 0), (-4, 3), (-5, 3), (4, 3), (9, 1)],
+            ),
+            (WrapMode::Clamp, [(-1, 0), (-4, 0), (-5, 0), (4, 3), (9, 3)]),
+        ];
+        for (mode, pairs) in cases.iter() {
+            for (i, expected) in pairs.iter() {
+                assert_eq!(mode.wrap(*i, 4), *expected);
+            }
+        }
+    }
+
+    #[test]
+    fn bilinear_texel_centers() {
+        // 2x1 图片, 左黑右白
+        let tex = ImageTexture::new_from_data(
+            2,
+            1,
+            vec![RGBColor::new(0., 0., 0.), RGBColor::new(1., 1., 1.)],
+            ColorSpace::Linear,
         )
+        .unwrap()
+        .with_wrap(WrapMode::Clamp);
+        let value = |u: f64| tex.value(u, 0.5, Point3::default(), Vec3::default(), 0.).x;
+
+        // 像素中心处为原像素值, 两中心之间线性插值, 中心以外按 wrap 取边缘
+        assert!(value(0.25).abs() < 1e-9);
+        assert!((value(0.75) - 1.).abs() < 1e-9);
+        assert!((value(0.5) - 0.5).abs() < 1e-9);
+        assert!((value(0.375) - 0.25).abs() < 1e-9);
+        assert!(value(0.1).abs() < 1e-9);
+
+        let nearest = tex.clone().with_filter(FilterMode::Nearest);
+        let col = nearest.value(0.49, 0.5, Point3::default(), Vec3::default(), 0.);
+        assert!(col.x.abs() < 1e-9);
+    }
+
+    #[test]
+    fn data_size_mismatch() {
+        let data = vec![RGBColor::default(); 5];
+        assert!(ImageTexture::new_from_data(2, 3, data.clone(), ColorSpace::Linear).is_err());
+        assert!(ImageTexture::new_from_data(0, 5, data.clone(), ColorSpace::Linear).is_err());
+        assert!(ImageTexture::new_from_data(5, 1, data, ColorSpace::Linear).is_ok());
     }
 }
@@ -1,11 +1,30 @@
 // pub mod checker_texture;
+pub mod blackbody;
 pub mod checker;
+pub mod combinator;
 pub mod gradient;
+pub mod image_sequence;
 pub mod image_texture;
+pub mod noise;
+pub mod noise_texture;
+pub mod registry;
 pub mod solid_color;
+pub mod texture_transform;
+pub mod triplanar;
+pub mod uv_pattern;
+pub mod voxel_grid;
 
-use crate::basic::vec3::{Point3, RGBColor};
+use std::sync::Arc;
+
+use crate::basic::vec3::{Point3, RGBColor, Vec3};
 
 pub trait Texture: Send + Sync {
-    fn value(&self, u: f64, v: f64, p: Point3) -> RGBColor;
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor;
+}
+
+// 以 Arc 共享的纹理, 多个材质引用同一纹理时无需复制
+impl<TT: Texture + ?Sized> Texture for Arc<TT> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        (**self).value(u, v, p, normal, tm)
+    }
 }
@@ -0,0 +1,204 @@
+use rand::{prelude::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::basic::vec3::{Point3, Vec3};
+
+const PERM_SIZE: usize = 256;
+
+// simplex noise 的 12 个梯度方向 (立方体各棱中点)
+const GRAD3: [[f64; 3]; 12] = [
+    [1., 1., 0.],
+    [-1., 1., 0.],
+    [1., -1., 0.],
+    [-1., -1., 0.],
+    [1., 0., 1.],
+    [-1., 0., 1.],
+    [1., 0., -1.],
+    [-1., 0., -1.],
+    [0., 1., 1.],
+    [0., -1., 1.],
+    [0., 1., -1.],
+    [0., -1., -1.],
+];
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+    let g = GRAD3[hash % 12];
+    g[0] * x + g[1] * y + g[2] * z
+}
+
+#[derive(Clone)]
+pub struct Noise {
+    // 三维噪声生成器, 置换表由 seed 决定, 同一 seed 在各线程中结果一致
+    perm: Vec<usize>,     // 长度 512, 前后两半相同以免取模
+    feature: Vec<Point3>, // Worley 噪声中每个格子内特征点的相对位置
+}
+
+impl Noise {
+    pub fn new(seed: u64) -> Self {
+        let mut rnd = StdRng::seed_from_u64(seed);
+        let mut perm: Vec<usize> = (0..PERM_SIZE).collect();
+        perm.shuffle(&mut rnd);
+        let copy = perm.clone();
+        perm.extend(copy);
+        let feature = (0..PERM_SIZE)
+            .map(|_| Point3::new(rnd.gen(), rnd.gen(), rnd.gen()))
+            .collect();
+        Self { perm, feature }
+    }
+
+    fn hash(&self, x: i64, y: i64, z: i64) -> usize {
+        let x = x.rem_euclid(PERM_SIZE as i64) as usize;
+        let y = y.rem_euclid(PERM_SIZE as i64) as usize;
+        let z = z.rem_euclid(PERM_SIZE as i64) as usize;
+        self.perm[self.perm[self.perm[x] + y] + z]
+    }
+
+    // Improved Perlin noise (Perlin 2002), 值域约为 [-1, 1]
+    pub fn perlin(&self, p: Point3) -> f64 {
+        let (xf, yf, zf) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (xi, yi, zi) = (xf as i64, yf as i64, zf as i64);
+        let (x, y, z) = (p.x - xf, p.y - yf, p.z - zf);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let g = |dx: i64, dy: i64, dz: i64| {
+            grad(
+                self.hash(xi + dx, yi + dy, zi + dz),
+                x - dx as f64,
+                y - dy as f64,
+                z - dz as f64,
+            )
+        };
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, g(0, 0, 0), g(1, 0, 0)),
+                lerp(u, g(0, 1, 0), g(1, 1, 0)),
+            ),
+            lerp(
+                v,
+                lerp(u, g(0, 0, 1), g(1, 0, 1)),
+                lerp(u, g(0, 1, 1), g(1, 1, 1)),
+            ),
+        )
+    }
+
+    // 3D simplex noise (Gustavson 2005), 值域约为 [-1, 1]
+    pub fn simplex(&self, p: Point3) -> f64 {
+        const F3: f64 = 1. / 3.;
+        const G3: f64 = 1. / 6.;
+
+        // 将输入空间斜切到单纯形网格上
+        let s = (p.x + p.y + p.z) * F3;
+        let (i, j, k) = (
+            (p.x + s).floor() as i64,
+            (p.y + s).floor() as i64,
+            (p.z + s).floor() as i64,
+        );
+        let t = (i + j + k) as f64 * G3;
+        let x0 = Vec3::new(
+            p.x - (i as f64 - t),
+            p.y - (j as f64 - t),
+            p.z - (k as f64 - t),
+        );
+
+        // 确定所在的单纯形 (共 6 种)
+        let (o1, o2) = if x0.x >= x0.y {
+            if x0.y >= x0.z {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0.x >= x0.z {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if x0.y < x0.z {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0.x < x0.z {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let corners = [(0, 0, 0), o1, o2, (1, 1, 1)];
+        let mut sum = 0.;
+        for (id, &(di, dj, dk)) in corners.iter().enumerate() {
+            let offset = id as f64 * G3;
+            let d = Vec3::new(
+                x0.x - di as f64 + offset,
+                x0.y - dj as f64 + offset,
+                x0.z - dk as f64 + offset,
+            );
+            let t = 0.6 - d.length_squared();
+            if t > 0. {
+                let h = self.hash(i + di, j + dj, k + dk);
+                sum += t.powi(4) * grad(h, d.x, d.y, d.z);
+            }
+        }
+        32. * sum
+    }
+
+    // Worley (cellular) noise, 返回到最近特征点的距离 F1, 值域约为 [0, 1]
+    pub fn worley(&self, p: Point3) -> f64 {
+        let (xf, yf, zf) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (xi, yi, zi) = (xf as i64, yf as i64, zf as i64);
+        let mut min_dis = f64::INFINITY;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let cell = Point3::new(xf + dx as f64, yf + dy as f64, zf + dz as f64);
+                    let feature = cell + self.feature[self.hash(xi + dx, yi + dy, zi + dz)];
+                    min_dis = min_dis.min((feature - p).length_squared());
+                }
+            }
+        }
+        min_dis.sqrt().min(1.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_points() -> Vec<Point3> {
+        let mut rnd = StdRng::seed_from_u64(7);
+        (0..2000)
+            .map(|_| Point3::new(rnd.gen(), rnd.gen(), rnd.gen()) * 20. - Vec3::new(10., 10., 10.))
+            .collect()
+    }
+
+    #[test]
+    fn deterministic_per_seed() {
+        let (a, b, c) = (Noise::new(42), Noise::new(42), Noise::new(43));
+        let points = sample_points();
+        for p in &points {
+            assert_eq!(a.perlin(*p), b.perlin(*p));
+            assert_eq!(a.simplex(*p), b.simplex(*p));
+            assert_eq!(a.worley(*p), b.worley(*p));
+        }
+        assert!(points.iter().any(|p| a.perlin(*p) != c.perlin(*p)));
+        assert!(points.iter().any(|p| a.worley(*p) != c.worley(*p)));
+    }
+
+    #[test]
+    fn value_range() {
+        let noise = Noise::new(19260817);
+        let points = sample_points();
+        for p in &points {
+            let (perlin, simplex, worley) = (noise.perlin(*p), noise.simplex(*p), noise.worley(*p));
+            assert!((-1. ..=1.).contains(&perlin));
+            assert!((-1. ..=1.).contains(&simplex));
+            assert!((0. ..=1.).contains(&worley));
+        }
+        // 整数格点处 Perlin 噪声为 0
+        assert!(noise.perlin(Point3::new(3., -2., 5.)).abs() < 1e-12);
+        // 非常数
+        assert!(points.iter().any(|p| noise.simplex(*p).abs() > 0.1));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::basic::{
+    clamp_oi,
+    vec3::{Point3, RGBColor, Vec3},
+};
+
+use super::{gradient::Gradient, noise::Noise, Texture};
+
+#[derive(Clone, Copy)]
+pub enum NoiseBasis {
+    Perlin,
+    Simplex,
+    Worley,
+}
+
+#[derive(Clone, Copy)]
+pub enum NoiseFractal {
+    Single,     // 单层噪声
+    Fbm,        // fractional Brownian motion, 各层噪声直接叠加
+    Turbulence, // 各层噪声取绝对值后叠加
+    Ridged,     // ridged multifractal, 1 - |n| 平方后叠加, 上一层结果调制下一层权重
+}
+
+#[derive(Clone)]
+pub struct NoiseTexture {
+    // 以碰撞点 p 为自变量的三维程序化噪声纹理, 噪声值 (归一化到 [0, 1]) 经 ramp 映射为颜色
+    pub noise: Noise,
+    pub basis: NoiseBasis,
+    pub fractal: NoiseFractal,
+    pub frequency: f64,  // 第一层噪声的频率
+    pub octaves: u32,    // 叠加层数
+    pub lacunarity: f64, // 相邻层频率之比
+    pub gain: f64,       // 相邻层振幅之比
+    pub ramp: Gradient,
+    pub velocity: Vec3, // 噪声整体随时间平移的速度
+    pub evolution: f64, // 噪声图案自身随时间变化的速度, 为 0 时图案不变
+}
+
+impl NoiseTexture {
+    pub fn new(seed: u64, basis: NoiseBasis, fractal: NoiseFractal, frequency: f64) -> Self {
+        Self {
+            noise: Noise::new(seed),
+            basis,
+            fractal,
+            frequency,
+            octaves: 6,
+            lacunarity: 2.,
+            gain: 0.5,
+            ramp: Gradient::new(
+                vec![RGBColor::new(0., 0., 0.), RGBColor::new(1., 1., 1.)],
+                vec![0., 1.],
+            )
+            .unwrap(),
+            velocity: Vec3::default(),
+            evolution: 0.,
+        }
+    }
+
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves.max(1);
+        self
+    }
+
+    pub fn with_lacunarity(mut self, lacunarity: f64) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    pub fn with_gain(mut self, gain: f64) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    pub fn with_ramp(mut self, ramp: Gradient) -> Self {
+        self.ramp = ramp;
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: Vec3) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn with_evolution(mut self, evolution: f64) -> Self {
+        self.evolution = evolution.max(0.);
+        self
+    }
+
+    // tm 时刻的噪声值: 先按 velocity 平移,
+    // 再在相隔较远 (互不相关) 的两个噪声切片间随时间平滑过渡, 近似四维噪声
+    pub fn noise_value_at(&self, p: Point3, tm: f64) -> f64 {
+        let p = p - self.velocity * tm;
+        if self.evolution <= 0. {
+            return self.noise_value(p);
+        }
+
+        let s = tm * self.evolution;
+        let (id, k) = (s.floor(), s - s.floor());
+        let k = k * k * (3. - 2. * k);
+        self.slice_value(p, id) * (1. - k) + self.slice_value(p, id + 1.) * k
+    }
+
+    // 第 id 个噪声切片, 即 tm * evolution = id 时刻的噪声值
+    fn slice_value(&self, p: Point3, id: f64) -> f64 {
+        let slice_offset = Vec3::new(113.5, 271.1, 337.7);
+        self.noise_value(p + slice_offset * id)
+    }
+
+    // 值域 [-1, 1] 的单层噪声
+    fn basis_value(&self, p: Point3) -> f64 {
+        match self.basis {
+            NoiseBasis::Perlin => self.noise.perlin(p),
+            NoiseBasis::Simplex => self.noise.simplex(p),
+            NoiseBasis::Worley => self.noise.worley(p) * 2. - 1.,
+        }
+    }
+
+    // 归一化到 [0, 1] 的噪声值
+    pub fn noise_value(&self, p: Point3) -> f64 {
+        let octaves = match self.fractal {
+            NoiseFractal::Single => 1,
+            _ => self.octaves,
+        };
+
+        let mut sum = 0.;
+        let mut amplitude_sum = 0.;
+        let mut amplitude = 1.;
+        let mut frequency = self.frequency;
+        let mut ridge_weight = 1.;
+        for _ in 0..octaves {
+            let n = self.basis_value(p * frequency);
+            sum += amplitude
+                * match self.fractal {
+                    NoiseFractal::Single | NoiseFractal::Fbm => (n + 1.) / 2.,
+                    NoiseFractal::Turbulence => n.abs(),
+                    NoiseFractal::Ridged => {
+                        let ridge = (1. - n.abs()).powi(2) * ridge_weight;
+                        ridge_weight = clamp_oi(ridge * 2., 0., 1.);
+                        ridge
+                    }
+                };
+            amplitude_sum += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+        }
+        clamp_oi(sum / amplitude_sum, 0., 1.)
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, p: Point3, _normal: Vec3, tm: f64) -> RGBColor {
+        self.ramp.color_at(self.noise_value_at(p, tm))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fractal_value_range() {
+        let bases = [NoiseBasis::Perlin, NoiseBasis::Simplex, NoiseBasis::Worley];
+        let fractals = [
+            NoiseFractal::Single,
+            NoiseFractal::Fbm,
+            NoiseFractal::Turbulence,
+            NoiseFractal::Ridged,
+        ];
+        for basis in bases.iter() {
+            for fractal in fractals.iter() {
+                let tex = NoiseTexture::new(1, *basis, *fractal, 0.7);
+                let same = NoiseTexture::new(1, *basis, *fractal, 0.7);
+                for i in 0..500 {
+                    let p = Point3::new(i as f64 * 0.37, i as f64 * -0.11, i as f64 * 0.05);
+                    let k = tex.noise_value(p);
+                    assert!((0. ..=1.).contains(&k));
+                    assert_eq!(k, same.noise_value(p));
+                    let col = tex.value(0., 0., p, Vec3::default(), 0.);
+                    assert!((col.x - k).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn time_interpolation() {
+        let p = Point3::new(1.3, -0.4, 2.2);
+        let velocity = Vec3::new(0.5, 0., -1.);
+
+        // 仅平移时, 随 velocity 移动的点处噪声值不变
+        let moving =
+            NoiseTexture::new(3, NoiseBasis::Perlin, NoiseFractal::Fbm, 1.).with_velocity(velocity);
+        for i in 0..5 {
+            let tm = i as f64 * 0.7;
+            assert!(
+                (moving.noise_value_at(p + velocity * tm, tm) - moving.noise_value(p)).abs()
+                    < 1e-12
+            );
+        }
+
+        // evolution > 0 时, tm * evolution 为整数的时刻恰为对应切片, 其间连续变化
+        let evolving =
+            NoiseTexture::new(3, NoiseBasis::Simplex, NoiseFractal::Fbm, 1.).with_evolution(2.);
+        for i in -2..4 {
+            let tm = i as f64 / 2.;
+            let slice = evolving.slice_value(p, i as f64);
+            assert!((evolving.noise_value_at(p, tm) - slice).abs() < 1e-12);
+            assert!((evolving.noise_value_at(p, tm + 1e-7) - slice).abs() < 1e-5);
+            assert!((evolving.noise_value_at(p, tm - 1e-7) - slice).abs() < 1e-5);
+
+            let (a, b) = (slice, evolving.slice_value(p, i as f64 + 1.));
+            let mid = evolving.noise_value_at(p, tm + 0.25);
+            assert!((mid - (a + b) / 2.).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::basic::color::ColorSpace;
+
+use super::{image_texture::ImageTexture, Texture};
+
+#[derive(Default)]
+pub struct TextureRegistry {
+    // 场景构建时共享的纹理表
+    // 图片在首次请求时才读取, 之后返回共享像素数据的副本, 同一文件只加载一次
+    images: Mutex<HashMap<(String, ColorSpace), ImageTexture>>,
+    named: Mutex<HashMap<String, NamedTexture>>,
+}
+
+// 同一纹理的两种句柄: 前者供按名字查询, 后者用于取回原类型
+type NamedTexture = (Arc<dyn Texture>, Arc<dyn Any + Send + Sync>);
+
+impl TextureRegistry {
+    // 返回的 ImageTexture 可各自设置过滤方式与 wrap mode, 像素数据仍然共享
+    pub fn image(&self, file_name: &str, color_space: ColorSpace) -> Result<ImageTexture, String> {
+        let key = (file_name.to_string(), color_space);
+        if let Some(texture) = self.images.lock().unwrap().get(&key) {
+            return Ok(texture.clone());
+        }
+
+        // 读取文件时不持有锁, 避免阻塞其他纹理的请求
+        let texture = ImageTexture::new_from_file(file_name, color_space)?;
+        Ok(self
+            .images
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert(texture)
+            .clone())
+    }
+
+    // 返回以 name 登记的纹理, 尚未登记时以 f 创建并登记; 返回可直接用于材质的共享句柄
+    // name 已登记为其他类型的纹理时返回错误
+    pub fn get_or_insert_with<TT, F>(&self, name: &str, f: F) -> Result<Arc<TT>, String>
+    where
+        TT: Texture + 'static,
+        F: FnOnce() -> TT,
+    {
+        let mut named = self.named.lock().unwrap();
+        let (_, any) = named.entry(name.to_string()).or_insert_with(|| {
+            let texture = Arc::new(f());
+            (texture.clone(), texture)
+        });
+        any.clone()
+            .downcast::<TT>()
+            .map_err(|_| format!("Texture \"{}\" is registered with another type", name))
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Texture>> {
+        self.named
+            .lock()
+            .unwrap()
+            .get(name)
+            .map(|(texture, _)| texture.clone())
+    }
+
+    pub fn image_count(&self) -> usize {
+        self.images.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use image::RgbImage;
+
+    use crate::{
+        basic::vec3::{Point3, Vec3},
+        texture::solid_color::SolidColor,
+    };
+
+    use super::*;
+
+    #[test]
+    fn shared_named_textures() {
+        let textures = TextureRegistry::default();
+        let a = textures
+            .get_or_insert_with("red", || SolidColor::new_from_value(1., 0., 0.))
+            .unwrap();
+        // 已登记时不再调用 f, 返回同一份纹理
+        let b = textures
+            .get_or_insert_with("red", || SolidColor::new_from_value(0., 1., 0.))
+            .unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!((b.color_value.x - 1.).abs() < 1e-9);
+
+        let c = textures.get("red").unwrap();
+        assert!(std::ptr::eq(
+            Arc::as_ptr(&a) as *const u8,
+            Arc::as_ptr(&c) as *const u8
+        ));
+        assert!(textures.get("blue").is_none());
+
+        let other = textures.get_or_insert_with("red", || {
+            ImageTexture::new_from_data(1, 1, vec![Default::default()], ColorSpace::Linear).unwrap()
+        });
+        assert!(other.is_err());
+    }
+
+    #[test]
+    fn image_cache_key() {
+        let path = std::env::temp_dir().join("texture_registry_test.png");
+        let path = path.to_str().unwrap();
+        RgbImage::from_pixel(2, 2, image::Rgb([128, 64, 32]))
+            .save(path)
+            .unwrap();
+
+        let textures = TextureRegistry::default();
+        let srgb = textures.image(path, ColorSpace::SRGB).unwrap();
+        let srgb_again = textures.image(path, ColorSpace::SRGB).unwrap();
+        assert_eq!(textures.image_count(), 1);
+        assert!(srgb.shares_pixels(&srgb_again));
+
+        // 颜色空间不同时分别解码并缓存
+        let linear = textures.image(path, ColorSpace::Linear).unwrap();
+        assert_eq!(textures.image_count(), 2);
+        assert!(!srgb.shares_pixels(&linear));
+        let value =
+            |tex: &ImageTexture| tex.value(0.5, 0.5, Point3::default(), Vec3::default(), 0.);
+        assert!(value(&linear).x > value(&srgb).x);
+
+        assert!(textures.image("not_exist.png", ColorSpace::SRGB).is_err());
+        assert_eq!(textures.image_count(), 2);
+        fs::remove_file(path).unwrap();
+    }
+}
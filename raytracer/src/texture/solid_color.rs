@@ -1,4 +1,4 @@
-use crate::basic::vec3::{Point3, RGBColor};
+use crate::basic::vec3::{Point3, RGBColor, Vec3};
 
 use super::Texture;
 
@@ -20,7 +20,7 @@ impl SolidColor {
 }
 
 impl Texture for SolidColor {
-    fn value(&self, _u: f64, _v: f64, _p: Point3) -> RGBColor {
+    fn value(&self, _u: f64, _v: f64, _p: Point3, _normal: Vec3, _tm: f64) -> RGBColor {
         self.color_value
     }
 }
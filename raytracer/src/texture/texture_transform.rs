@@ -0,0 +1,227 @@
+use crate::basic::{
+    degree_to_radian,
+    vec3::{Point3, RGBColor, Vec3},
+};
+
+use super::Texture;
+
+#[derive(Clone)]
+pub struct UVTransform<TT>
+where
+    TT: Texture,
+{
+    // 采样子纹理前变换纹理坐标: 先缩放, 再绕 (0, 0) 旋转, 最后平移
+    pub texture: TT,
+    pub scale: (f64, f64),
+    pub offset: (f64, f64),
+    sin_theta: f64,
+    cos_theta: f64,
+}
+
+impl<TT: Texture> UVTransform<TT> {
+    pub fn new(texture: TT) -> Self {
+        Self {
+            texture,
+            scale: (1., 1.),
+            offset: (0., 0.),
+            sin_theta: 0.,
+            cos_theta: 1.,
+        }
+    }
+
+    pub fn with_scale(mut self, scale_u: f64, scale_v: f64) -> Self {
+        self.scale = (scale_u, scale_v);
+        self
+    }
+
+    pub fn with_rotation(mut self, angle: f64) -> Self {
+        let radians = degree_to_radian(angle);
+        self.sin_theta = radians.sin();
+        self.cos_theta = radians.cos();
+        self
+    }
+
+    pub fn with_offset(mut self, offset_u: f64, offset_v: f64) -> Self {
+        self.offset = (offset_u, offset_v);
+        self
+    }
+
+    pub fn transform(&self, u: f64, v: f64) -> (f64, f64) {
+        let (u, v) = (u * self.scale.0, v * self.scale.1);
+        (
+            self.cos_theta * u - self.sin_theta * v + self.offset.0,
+            self.sin_theta * u + self.cos_theta * v + self.offset.1,
+        )
+    }
+}
+
+impl<TT: Texture> Texture for UVTransform<TT> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        let (u, v) = self.transform(u, v);
+        self.texture.value(u, v, p, normal, tm)
+    }
+}
+
+//=================================================
+
+#[derive(Clone)]
+pub struct PointTransform<TT>
+where
+    TT: Texture,
+{
+    // 采样子纹理前变换碰撞点: 先平移, 再绕坐标轴旋转, 最后缩放
+    // 例如平移物体中心至原点后, 可得到物体空间中的三维纹理
+    pub texture: TT,
+    pub offset: Vec3,
+    pub scale: Vec3,
+    dir: usize, // 旋转轴, 与 Rotate 相同 0/1/2 分别为 x/y/z
+    sin_theta: f64,
+    cos_theta: f64,
+}
+
+impl<TT: Texture> PointTransform<TT> {
+    pub fn new(texture: TT) -> Self {
+        Self {
+            texture,
+            offset: Vec3::default(),
+            scale: Vec3::new(1., 1., 1.),
+            dir: 1,
+            sin_theta: 0.,
+            cos_theta: 1.,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_rotation(mut self, dir: usize, angle: f64) -> Self {
+        if dir > 2 {
+            panic!("Get unexpected dir in PointTransform::with_rotation!");
+        }
+        let radians = degree_to_radian(angle);
+        self.dir = dir;
+        self.sin_theta = radians.sin();
+        self.cos_theta = radians.cos();
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn transform(&self, p: Point3) -> Point3 {
+        let p = p + self.offset;
+        let (a, b) = ((self.dir + 1) % 3, (self.dir + 2) % 3);
+        let mut ret = p;
+        ret[a] = self.cos_theta * p[a] - self.sin_theta * p[b];
+        ret[b] = self.sin_theta * p[a] + self.cos_theta * p[b];
+        ret * self.scale
+    }
+
+    // 法向量仅随旋转变换, 供 Triplanar 等依赖法向量的纹理使用
+    pub fn transform_normal(&self, normal: Vec3) -> Vec3 {
+        let (a, b) = ((self.dir + 1) % 3, (self.dir + 2) % 3);
+        let mut ret = normal;
+        ret[a] = self.cos_theta * normal[a] - self.sin_theta * normal[b];
+        ret[b] = self.sin_theta * normal[a] + self.cos_theta * normal[b];
+        ret
+    }
+}
+
+impl<TT: Texture> Texture for PointTransform<TT> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        self.texture
+            .value(u, v, self.transform(p), self.transform_normal(normal), tm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 将输入的 u,v / 碰撞点 / 法向量原样输出为颜色
+    #[derive(Clone)]
+    struct UVProbe;
+
+    impl Texture for UVProbe {
+        fn value(&self, u: f64, v: f64, _p: Point3, _normal: Vec3, _tm: f64) -> RGBColor {
+            RGBColor::new(u, v, 0.)
+        }
+    }
+
+    #[derive(Clone)]
+    struct PointProbe;
+
+    impl Texture for PointProbe {
+        fn value(&self, _u: f64, _v: f64, p: Point3, _normal: Vec3, _tm: f64) -> RGBColor {
+            p
+        }
+    }
+
+    #[derive(Clone)]
+    struct NormalProbe;
+
+    impl Texture for NormalProbe {
+        fn value(&self, _u: f64, _v: f64, _p: Point3, normal: Vec3, _tm: f64) -> RGBColor {
+            normal
+        }
+    }
+
+    fn near(a: Vec3, b: Vec3) -> bool {
+        (a - b).length() < 1e-9
+    }
+
+    #[test]
+    fn uv_transform_order() {
+        let tex = UVTransform::new(UVProbe)
+            .with_scale(2., 1.)
+            .with_rotation(90.)
+            .with_offset(0.5, 0.);
+        // (1, 1) -> 缩放 (2, 1) -> 旋转 (-1, 2) -> 平移 (-0.5, 2)
+        let col = tex.value(1., 1., Point3::default(), Vec3::default(), 0.);
+        assert!(near(col, RGBColor::new(-0.5, 2., 0.)));
+
+        let identity = UVTransform::new(UVProbe);
+        let col = identity.value(0.3, 0.7, Point3::default(), Vec3::default(), 0.);
+        assert!(near(col, RGBColor::new(0.3, 0.7, 0.)));
+    }
+
+    #[test]
+    fn point_transform_order() {
+        let tex = PointTransform::new(PointProbe)
+            .with_offset(Vec3::new(1., 0., 0.))
+            .with_rotation(2, 90.)
+            .with_scale(Vec3::new(2., 1., 1.));
+        // (0, 1, 0) -> 平移 (1, 1, 0) -> 绕 z 轴旋转 (-1, 1, 0) -> 缩放 (-2, 1, 0)
+        let col = tex.value(0., 0., Point3::new(0., 1., 0.), Vec3::default(), 0.);
+        assert!(near(col, Point3::new(-2., 1., 0.)));
+
+        // 绕各轴旋转 90° (右手系): x 轴 y -> z, y 轴 z -> x, z 轴 x -> y
+        let axes = [
+            (0, Vec3::new(0., 1., 0.), Vec3::new(0., 0., 1.)),
+            (1, Vec3::new(0., 0., 1.), Vec3::new(1., 0., 0.)),
+            (2, Vec3::new(1., 0., 0.), Vec3::new(0., 1., 0.)),
+        ];
+        for (dir, from, to) in axes.iter() {
+            let tex = PointTransform::new(PointProbe).with_rotation(*dir, 90.);
+            assert!(near(tex.transform(*from), *to));
+        }
+    }
+
+    #[test]
+    fn normal_ignores_offset_and_scale() {
+        let tex = PointTransform::new(NormalProbe)
+            .with_offset(Vec3::new(3., -2., 1.))
+            .with_rotation(2, 90.)
+            .with_scale(Vec3::new(5., 5., 5.));
+        let col = tex.value(0., 0., Point3::default(), Vec3::new(1., 0., 0.), 0.);
+        assert!(near(col, Vec3::new(0., 1., 0.)));
+        assert!(near(
+            tex.transform_normal(Vec3::new(0., 0., 1.)),
+            Vec3::new(0., 0., 1.)
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::basic::vec3::{Point3, RGBColor, Vec3};
+
+use super::Texture;
+
+#[derive(Clone)]
+pub struct Triplanar<TT>
+where
+    TT: Texture,
+{
+    // 三平面投影: 沿 x/y/z 轴将纹理投影到物体表面, 按法向量分量混合, 用于没有 u,v 的物体
+    // 默认使用世界坐标轴, 套一层 PointTransform 即可改用物体坐标轴
+    pub texture: TT,
+    pub scale: f64,     // 单位长度对应的纹理坐标
+    pub sharpness: f64, // 混合权重为法向量分量绝对值的 sharpness 次方, 越大过渡越窄
+}
+
+impl<TT: Texture> Triplanar<TT> {
+    pub fn new(texture: TT, scale: f64) -> Self {
+        Self {
+            texture,
+            scale,
+            sharpness: 4.,
+        }
+    }
+
+    pub fn with_sharpness(mut self, sharpness: f64) -> Self {
+        self.sharpness = sharpness.max(0.);
+        self
+    }
+}
+
+impl<TT: Texture> Texture for Triplanar<TT> {
+    fn value(&self, _u: f64, _v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        let mut weight = [
+            normal.x.abs().powf(self.sharpness),
+            normal.y.abs().powf(self.sharpness),
+            normal.z.abs().powf(self.sharpness),
+        ];
+        let sum: f64 = weight.iter().sum();
+        if sum > 0. {
+            weight.iter_mut().for_each(|w| *w /= sum);
+        } else {
+            // 没有法向量信息 (如介质内部), 三个方向平均
+            weight = [1. / 3.; 3];
+        }
+
+        let q = p * self.scale;
+        let planes = [(q.z, q.y), (q.x, q.z), (q.x, q.y)]; // 分别沿 x/y/z 轴投影
+        let mut col = RGBColor::default();
+        for (w, (u, v)) in weight.iter().zip(planes.iter()) {
+            if *w > 0. {
+                col += self.texture.value(*u, *v, p, normal, tm) * *w;
+            }
+        }
+        col
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::texture::solid_color::SolidColor;
+
+    use super::*;
+
+    // 将投影得到的纹理坐标输出为颜色
+    #[derive(Clone)]
+    struct UVProbe;
+
+    impl Texture for UVProbe {
+        fn value(&self, u: f64, v: f64, _p: Point3, _normal: Vec3, _tm: f64) -> RGBColor {
+            RGBColor::new(u, v, 0.)
+        }
+    }
+
+    fn near(a: RGBColor, b: RGBColor) -> bool {
+        (a - b).length() < 1e-9
+    }
+
+    #[test]
+    fn axis_aligned_normal() {
+        let tex = Triplanar::new(UVProbe, 1.);
+        let p = Point3::new(1., 2., 3.);
+        let at = |normal: Vec3| tex.value(0., 0., p, normal, 0.);
+
+        // 法向量沿坐标轴时仅使用对应平面, 与法向量朝向无关
+        assert!(near(at(Vec3::new(1., 0., 0.)), RGBColor::new(3., 2., 0.)));
+        assert!(near(at(Vec3::new(0., -1., 0.)), RGBColor::new(1., 3., 0.)));
+        assert!(near(at(Vec3::new(0., 0., 1.)), RGBColor::new(1., 2., 0.)));
+        // 位于两轴之间时两平面各占一半
+        let diagonal = Vec3::new(1., 1., 0.).to_unit();
+        assert!(near(at(diagonal), RGBColor::new(2., 2.5, 0.)));
+
+        let scaled = Triplanar::new(UVProbe, 0.5);
+        let col = scaled.value(0., 0., p, Vec3::new(0., 0., 1.), 0.);
+        assert!(near(col, RGBColor::new(0.5, 1., 0.)));
+    }
+
+    #[test]
+    fn weights_sum_to_one() {
+        let white = RGBColor::new(1., 1., 1.);
+        for &sharpness in &[0., 1., 4., 16.] {
+            let tex = Triplanar::new(SolidColor::new(white), 1.).with_sharpness(sharpness);
+            for normal in &[
+                Vec3::new(0.3, -0.5, 0.8).to_unit(),
+                Vec3::new(-1., 2., 0.).to_unit(),
+                Vec3::new(0., 0., -1.),
+                Vec3::default(),
+            ] {
+                let col = tex.value(0., 0., Point3::new(1., 2., 3.), *normal, 0.);
+                assert!(near(col, white));
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::basic::vec3::{Point3, RGBColor, Vec3};
+
+use super::Texture;
+
+#[derive(Clone, Copy)]
+pub enum UVPatternKind {
+    // 以下长度均以单个图案单元 (tile) 为 1
+    Checker,
+    Stripes {
+        width: f64,    // 条纹所占比例
+        along_u: bool, // 为真时条纹随 u 交替, 否则随 v 交替
+    },
+    Grid {
+        line_width: f64,
+    },
+    Dots {
+        radius: f64, // 圆点位于单元中心
+    },
+    Bricks {
+        mortar: f64,     // 砖缝宽度
+        row_offset: f64, // 奇数行相对偶数行的错位
+    },
+}
+
+#[derive(Clone)]
+pub struct UVPattern<TT1, TT2>
+where
+    TT1: Texture,
+    TT2: Texture,
+{
+    // UV 空间中的重复图案, 图案部分 (棋盘格的奇数格, 条纹, 网格线, 圆点, 砖缝) 取 pattern, 其余取 base
+    pub kind: UVPatternKind,
+    pub pattern: TT1,
+    pub base: TT2,
+    pub tile_u: f64, // u 方向上 [0, 1] 内的单元数
+    pub tile_v: f64,
+}
+
+impl<TT1: Texture, TT2: Texture> UVPattern<TT1, TT2> {
+    pub fn new(kind: UVPatternKind, pattern: TT1, base: TT2, tile_u: f64, tile_v: f64) -> Self {
+        Self {
+            kind,
+            pattern,
+            base,
+            tile_u,
+            tile_v,
+        }
+    }
+
+    pub fn is_pattern(&self, u: f64, v: f64) -> bool {
+        let (x, y) = (u * self.tile_u, v * self.tile_v);
+        let (fx, fy) = (x - x.floor(), y - y.floor());
+        match self.kind {
+            UVPatternKind::Checker => (x.floor() + y.floor()).rem_euclid(2.) > 0.5,
+            UVPatternKind::Stripes { width, along_u } => {
+                if along_u {
+                    fx < width
+                } else {
+                    fy < width
+                }
+            }
+            UVPatternKind::Grid { line_width } => {
+                let half = line_width / 2.;
+                fx < half || fx > 1. - half || fy < half || fy > 1. - half
+            }
+            UVPatternKind::Dots { radius } => {
+                (fx - 0.5).powi(2) + (fy - 0.5).powi(2) < radius * radius
+            }
+            UVPatternKind::Bricks { mortar, row_offset } => {
+                let shifted = x + row_offset * y.floor().rem_euclid(2.);
+                let fx = shifted - shifted.floor();
+                let half = mortar / 2.;
+                fx < half || fx > 1. - half || fy < half || fy > 1. - half
+            }
+        }
+    }
+}
+
+impl<TT1: Texture, TT2: Texture> Texture for UVPattern<TT1, TT2> {
+    fn value(&self, u: f64, v: f64, p: Point3, normal: Vec3, tm: f64) -> RGBColor {
+        if self.is_pattern(u, v) {
+            self.pattern.value(u, v, p, normal, tm)
+        } else {
+            self.base.value(u, v, p, normal, tm)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::texture::solid_color::SolidColor;
+
+    use super::*;
+
+    fn pattern(kind: UVPatternKind) -> UVPattern<SolidColor, SolidColor> {
+        UVPattern::new(
+            kind,
+            SolidColor::new_from_value(1., 1., 1.),
+            SolidColor::new_from_value(0., 0., 0.),
+            4.,
+            2.,
+        )
+    }
+
+    #[test]
+    fn pattern_kinds() {
+        // u 方向 4 个单元, v 方向 2 个单元
+        let checker = pattern(UVPatternKind::Checker);
+        assert!(!checker.is_pattern(0.1, 0.1));
+        assert!(checker.is_pattern(0.3, 0.1));
+        assert!(checker.is_pattern(0.1, 0.6));
+        assert!(!checker.is_pattern(-0.1, 0.6)); // 负坐标同样交替
+        let col = checker.value(0.3, 0.1, Point3::default(), Vec3::default(), 0.);
+        assert!((col.x - 1.).abs() < 1e-9);
+
+        let stripes = pattern(UVPatternKind::Stripes {
+            width: 0.25,
+            along_u: true,
+        });
+        assert!(stripes.is_pattern(0.05, 0.9) && !stripes.is_pattern(0.1, 0.9));
+
+        let dots = pattern(UVPatternKind::Dots { radius: 0.2 });
+        assert!(dots.is_pattern(0.125, 0.25) && !dots.is_pattern(0.01, 0.01));
+
+        let grid = pattern(UVPatternKind::Grid { line_width: 0.1 });
+        assert!(grid.is_pattern(0.01, 0.3) && !grid.is_pattern(0.125, 0.25));
+
+        // 奇数行砖块错开半个单元
+        let bricks = pattern(UVPatternKind::Bricks {
+            mortar: 0.1,
+            row_offset: 0.5,
+        });
+        assert!(bricks.is_pattern(0.005, 0.25) && !bricks.is_pattern(0.005, 0.75));
+        assert!(!bricks.is_pattern(0.125, 0.25) && bricks.is_pattern(0.125, 0.75));
+    }
+}
@@ -0,0 +1,172 @@
+use std::fs;
+
+use crate::basic::vec3::{Point3, RGBColor, Vec3};
+
+use super::Texture;
+
+#[derive(Clone)]
+pub struct VoxelGrid {
+    // 三维体素网格, 在 [min, max] 范围内三线性插值, 范围外为 0
+    // 用作非均匀介质的密度场
+    pub size: [usize; 3],
+    pub data: Vec<f64>, // x 变化最快, 其次为 y, z
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl VoxelGrid {
+    pub fn new(size: [usize; 3], data: Vec<f64>, min: Point3, max: Point3) -> Result<Self, String> {
+        if size.contains(&0) {
+            return Err(format!(
+                "Voxel grid size must be positive: {}x{}x{}",
+                size[0], size[1], size[2]
+            ));
+        }
+        if data.len() != size[0] * size[1] * size[2] {
+            return Err(format!(
+                "Voxel grid expects {} values but gets {}",
+                size[0] * size[1] * size[2],
+                data.len()
+            ));
+        }
+
+        Ok(Self {
+            size,
+            data,
+            min,
+            max,
+        })
+    }
+
+    // 文本格式: 前三个整数为 nx ny nz, 其后为 nx * ny * nz 个体素值, 以空白分隔
+    pub fn load_from_file(file_name: &str, min: Point3, max: Point3) -> Result<Self, String> {
+        let text = fs::read_to_string(file_name).map_err(|e| {
+            format!(
+                "Opening voxel grid fails! File name: \"{}\" ({})",
+                file_name, e
+            )
+        })?;
+        let mut tokens = text.split_whitespace();
+
+        let mut size = [0; 3];
+        for s in &mut size {
+            *s = tokens
+                .next()
+                .and_then(|t| t.parse::<usize>().ok())
+                .ok_or_else(|| format!("Invalid voxel grid size in \"{}\"", file_name))?;
+        }
+        let data = tokens
+            .map(|t| {
+                t.parse::<f64>()
+                    .map_err(|_| format!("Invalid voxel value \"{}\" in \"{}\"", t, file_name))
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+
+        Self::new(size, data, min, max).map_err(|e| format!("{} in \"{}\"", e, file_name))
+    }
+
+    pub fn max_value(&self) -> f64 {
+        self.data.iter().copied().fold(0., f64::max)
+    }
+
+    fn voxel(&self, x: usize, y: usize, z: usize) -> f64 {
+        self.data[(z * self.size[1] + y) * self.size[0] + x]
+    }
+
+    pub fn density(&self, p: &Point3) -> f64 {
+        let mut idx = [0; 3];
+        let mut k = [0.; 3];
+        for i in 0..3 {
+            let extent = self.max[i] - self.min[i];
+            if extent <= 0. || p[i] < self.min[i] || p[i] > self.max[i] {
+                return 0.;
+            }
+            // 体素值位于体素中心
+            let x = (p[i] - self.min[i]) / extent * self.size[i] as f64 - 0.5;
+            let x = x.max(0.).min((self.size[i] - 1) as f64);
+            idx[i] = (x as usize).min(self.size[i].saturating_sub(2));
+            k[i] = x - idx[i] as f64;
+        }
+
+        let mut sum = 0.;
+        for dz in 0..2 {
+            for dy in 0..2 {
+                for dx in 0..2 {
+                    let x = (idx[0] + dx).min(self.size[0] - 1);
+                    let y = (idx[1] + dy).min(self.size[1] - 1);
+                    let z = (idx[2] + dz).min(self.size[2] - 1);
+                    let w = (if dx == 0 { 1. - k[0] } else { k[0] })
+                        * (if dy == 0 { 1. - k[1] } else { k[1] })
+                        * (if dz == 0 { 1. - k[2] } else { k[2] });
+                    sum += w * self.voxel(x, y, z);
+                }
+            }
+        }
+        sum
+    }
+}
+
+impl Texture for VoxelGrid {
+    fn value(&self, _u: f64, _v: f64, p: Point3, _normal: Vec3, _tm: f64) -> RGBColor {
+        let d = self.density(&p);
+        RGBColor::new(d, d, d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trilinear_density() {
+        // 2x1x1 网格, 覆盖 [0, 2] x [0, 1] x [0, 1], 体素中心位于 x = 0.5 与 x = 1.5
+        let grid = VoxelGrid::new(
+            [2, 1, 1],
+            vec![1., 3.],
+            Point3::new(0., 0., 0.),
+            Point3::new(2., 1., 1.),
+        )
+        .unwrap();
+        let at = |x: f64| grid.density(&Point3::new(x, 0.5, 0.5));
+        assert!((at(0.5) - 1.).abs() < 1e-9);
+        assert!((at(1.5) - 3.).abs() < 1e-9);
+        assert!((at(1.) - 2.).abs() < 1e-9);
+        assert!((at(0.1) - 1.).abs() < 1e-9); // 中心以外取边缘体素
+        assert!(at(2.5) == 0. && at(-0.1) == 0.);
+        assert!((grid.max_value() - 3.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn invalid_size() {
+        let (min, max) = (Point3::default(), Point3::new(1., 1., 1.));
+        assert!(VoxelGrid::new([0, 1, 1], vec![], min, max).is_err());
+        assert!(VoxelGrid::new([2, 0, 3], vec![], min, max).is_err());
+        assert!(VoxelGrid::new([2, 2, 1], vec![1.; 3], min, max).is_err());
+        assert!(VoxelGrid::new([1, 1, 1], vec![1.], min, max).is_ok());
+
+        let path = std::env::temp_dir().join("voxel_grid_size_test.txt");
+        let path = path.to_str().unwrap();
+        fs::write(path, "2 0 1\n").unwrap();
+        assert!(VoxelGrid::load_from_file(path, min, max).is_err());
+        fs::write(path, "2 2 1\n0.5 0.25").unwrap();
+        assert!(VoxelGrid::load_from_file(path, min, max).is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn load_errors() {
+        let (min, max) = (Point3::default(), Point3::new(1., 1., 1.));
+        assert!(VoxelGrid::load_from_file("raytracer/model/not_exist.txt", min, max).is_err());
+
+        let path = std::env::temp_dir().join("voxel_grid_test.txt");
+        let path = path.to_str().unwrap();
+        fs::write(path, "2 1 1\n0.5 0.25").unwrap();
+        let grid = VoxelGrid::load_from_file(path, min, max).unwrap();
+        assert_eq!(grid.size, [2, 1, 1]);
+        fs::write(path, "2 2 1\n0.5 0.25").unwrap();
+        assert!(VoxelGrid::load_from_file(path, min, max).is_err());
+        fs::write(path, "2 1 1\n0.5 x").unwrap();
+        assert!(VoxelGrid::load_from_file(path, min, max).is_err());
+        fs::remove_file(path).unwrap();
+    }
+}